@@ -2,6 +2,7 @@ use slashy::{
     command,
     commands::CommandResult,
     framework::{CommandContext, Framework},
+    help::HELP_COMMAND,
     serenity::{prelude::GatewayIntents, Client},
     settings::Settings,
     subcommand,
@@ -36,11 +37,14 @@ async fn main() {
         prefixes: vec!["!"],
         auto_register: true,
         auto_delete: true,
-        slash_command_guilds: vec![],
+        ..Default::default()
     };
     let framework = Framework::new(settings, app_id, token.clone())
         .await
-        .command::<PING_COMMAND>();
+        .expect("Error creating framework")
+        .command::<PING_COMMAND>()
+        .and_then(|f| f.command::<HELP_COMMAND>())
+        .expect("Error adding commands");
 
     // Login with a bot token from the environment
     let mut client = Client::builder(
@@ -0,0 +1,218 @@
+use serenity::model::id::ChannelId;
+
+use crate::{
+    command,
+    commands::{CommandGroup, CommandResult, SlashyError},
+    framework::{CommandContext, CommandInit, GroupInit},
+    permissions::ADMINISTRATOR,
+    subcommand,
+};
+
+command! {
+    commands,
+    "enables or disables commands in this server or a channel",
+    [
+        optional SubCommand enable = enable | "enables a command" [
+            required String command | "the command or subcommand to enable, eg. `stats get`",
+            optional Channel channel | "the channel to enable it in, defaults to the whole server"
+        ],
+        optional SubCommand disable = disable | "disables a command" [
+            required String command | "the command or subcommand to disable, eg. `stats get`",
+            optional Channel channel | "the channel to disable it in, defaults to the whole server"
+        ],
+        optional SubCommandGroup group | "enables or disables every command in a group" [
+            optional SubCommand enable = enable_group | "enables every command in a group" [
+                required String group | "the group to enable",
+                optional Channel channel | "the channel to enable it in, defaults to the whole server"
+            ],
+            optional SubCommand disable = disable_group | "disables every command in a group" [
+                required String group | "the group to disable",
+                optional Channel channel | "the channel to disable it in, defaults to the whole server"
+            ]
+        ]
+    ]
+}
+
+/// The name of the group the admin commands are in
+const ADMIN: &str = "admin";
+
+/// The group of built in commands for server admins
+///
+/// Contains `commands enable`, `commands disable` and `commands group enable|disable` to toggle every command in a group.<br>
+/// Add it with `.group::<ADMIN_GROUP>()`, the changes are stored through the [SettingsProvider](crate::settings::SettingsProvider)
+#[allow(non_camel_case_types)]
+pub struct ADMIN_GROUP;
+impl GroupInit for ADMIN_GROUP {
+    fn group_init() -> CommandGroup {
+        CommandGroup {
+            name: ADMIN,
+            description: "commands for server admins",
+            checks: vec![ADMINISTRATOR],
+            works_in_dms: false,
+            commands: vec![COMMANDS_COMMAND::command_init()],
+        }
+    }
+}
+
+#[subcommand]
+async fn enable(ctx: &CommandContext) -> CommandResult {
+    set_enabled(ctx, true).await
+}
+
+#[subcommand]
+async fn disable(ctx: &CommandContext) -> CommandResult {
+    set_enabled(ctx, false).await
+}
+
+#[subcommand]
+async fn enable_group(ctx: &CommandContext) -> CommandResult {
+    set_group_enabled(ctx, true).await
+}
+
+#[subcommand]
+async fn disable_group(ctx: &CommandContext) -> CommandResult {
+    set_group_enabled(ctx, false).await
+}
+
+async fn set_enabled(ctx: &CommandContext, enabled: bool) -> CommandResult {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| SlashyError::new("Commands can only be toggled in servers"))?;
+    let input = ctx
+        .get_str_arg("command")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let channel_id = ctx.get_channel_arg("channel").copied();
+
+    let case_insensitive = ctx.settings().case_insensitive().await;
+    let (path, group) = resolve_path(ctx, input, case_insensitive)?;
+    if !enabled && group == Some(ADMIN) {
+        return Err(Box::new(SlashyError::new(
+            "Admin commands can't be disabled",
+        )));
+    }
+
+    // Enabling has no effect while a parent of the command is still disabled
+    let mut blocked_by = None;
+    if enabled {
+        // No channel has the id 0, so it only has the guild's settings
+        let channel = channel_id.unwrap_or(ChannelId(0));
+        let words = path.split(' ').collect::<Vec<_>>();
+        for end in 1 .. words.len() {
+            let key = words[.. end].join(" ");
+            if !ctx
+                .settings()
+                .command_enabled(guild_id, channel, &key)
+                .await
+            {
+                blocked_by = Some(key);
+                break;
+            }
+        }
+    }
+
+    ctx.settings()
+        .set_command_enabled(guild_id, channel_id, &path, enabled)
+        .await?;
+
+    let action = if enabled { "Enabled" } else { "Disabled" };
+    let location = match channel_id {
+        Some(c) => format!("<#{}>", c.0),
+        None => "this server".to_owned(),
+    };
+    let mut message = format!("{action} `{path}` in {location}");
+    if let Some(key) = blocked_by {
+        message.push_str(&format!(
+            ", it can't be used there until `{key}` is enabled too"
+        ));
+    }
+    ctx.send_str(&message).await?;
+
+    Ok(())
+}
+
+async fn set_group_enabled(ctx: &CommandContext, enabled: bool) -> CommandResult {
+    let guild_id = ctx
+        .guild_id()
+        .ok_or_else(|| SlashyError::new("Commands can only be toggled in servers"))?;
+    let input = ctx
+        .get_str_arg("group")
+        .map(String::as_str)
+        .unwrap_or_default();
+    let channel_id = ctx.get_channel_arg("channel").copied();
+
+    let case_insensitive = ctx.settings().case_insensitive().await;
+    let mut group = None;
+    let mut names = Vec::new();
+    for cmd in ctx.commands().values() {
+        let name = match cmd.group {
+            Some(g) if g == input || (case_insensitive && g.eq_ignore_ascii_case(input)) => g,
+            _ => continue,
+        };
+        group = Some(name);
+        names.push(cmd.name);
+    }
+    let group = group.ok_or_else(|| SlashyError::new(&format!("No group named `{input}`")))?;
+    if !enabled && group == ADMIN {
+        return Err(Box::new(SlashyError::new(
+            "Admin commands can't be disabled",
+        )));
+    }
+
+    names.sort_unstable();
+    for name in &names {
+        ctx.settings()
+            .set_command_enabled(guild_id, channel_id, name, enabled)
+            .await?;
+    }
+
+    let action = if enabled { "Enabled" } else { "Disabled" };
+    let location = match channel_id {
+        Some(c) => format!("<#{}>", c.0),
+        None => "this server".to_owned(),
+    };
+    ctx.send_str(&format!(
+        "{action} the {} commands in `{group}` in {location}",
+        names.len()
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Turns what the user typed into the real names of the command and subcommands
+///
+/// Returns the path and the group of the command
+fn resolve_path(
+    ctx: &CommandContext,
+    input: &str,
+    case_insensitive: bool,
+) -> CommandResult<(String, Option<&'static str>)> {
+    let words = input.split_whitespace().collect::<Vec<_>>();
+    let name = match words.first() {
+        Some(n) if case_insensitive => n.to_lowercase(),
+        Some(n) => n.to_string(),
+        None => return Err(Box::new(SlashyError::new("No command given"))),
+    };
+    let cmd = ctx
+        .commands()
+        .get(name.as_str())
+        .ok_or_else(|| SlashyError::new(&format!("No command named `{name}`")))?;
+
+    let subcommands = &words[1 ..];
+    let found = cmd.arguments_tree.find_path(subcommands, case_insensitive);
+    if let Some(word) = subcommands.get(found.len()) {
+        return Err(Box::new(SlashyError::new(&format!(
+            "`{word}` is not a subcommand of `{}`",
+            words[.. found.len() + 1].join(" ")
+        ))));
+    }
+
+    let mut path = cmd.name.to_owned();
+    for node in found {
+        path.push(' ');
+        path.push_str(node.name());
+    }
+
+    Ok((path, cmd.group))
+}
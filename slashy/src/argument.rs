@@ -14,7 +14,13 @@ use serenity::{
 use regex::Regex;
 
 use crate::{
-    commands::{CommandArguments, CommandArgumentsTree, CommandFunction},
+    commands::{
+        ArgumentChoice,
+        CommandArguments,
+        CommandArgumentsTree,
+        CommandFunction,
+        SlashyError,
+    },
     framework::CommandSource,
 };
 
@@ -71,7 +77,7 @@ macro_rules! cmp_arg_interaction {
 }
 
 macro_rules! arg_message {
-    ($str_args: ident, $branch: ident, $map: ident, $func: ident, $arg: ident,
+    ($str_args: ident, $branch: ident, $map: ident, $func: ident, $arg: ident, $case_insensitive: ident,
     $($arg_type: ident, $self_arg: ident, $parser: ident);* |
     $($sub_command_type: ident),*) => {
         match $arg {
@@ -89,27 +95,22 @@ macro_rules! arg_message {
                     }
                 }
             },)*
-            $(CommandArguments::$sub_command_type {name, required, func, options, ..} => {
-                let next = $str_args.peek();
-                if *required {
-                    match next {
-                        Some(str) => {
-                            if str != &name {
-                                return None
-                            }
-                        },
-                        None => return None
-                    }
+            $(CommandArguments::$sub_command_type {required, func, options, ..} => {
+                let matched = match $str_args.peek() {
+                    Some(str) => $arg.matches_name(str, $case_insensitive),
+                    None => false
+                };
+
+                if *required && !matched {
+                    return None
                 }
 
-                if next.is_some() {
-                    if &name == $str_args.peek().unwrap() {
-                        $str_args.next();
-                        $func = match options {
-                            Some(v) => Self::parse_str($str_args, v, $map, func.clone()),
-                            None => func.clone()
-                        };
-                    }
+                if matched {
+                    $str_args.next();
+                    $func = match options {
+                        Some(v) => Self::parse_str($str_args, v, $map, func.clone(), $case_insensitive),
+                        None => func.clone()
+                    };
                 }
             },)*
         }
@@ -207,6 +208,20 @@ impl Argument {
         fun
     }
 
+    /// Gets the names of the SubCommands and SubCommandGroups an interaction was sent for
+    pub fn interaction_path(interaction: &ApplicationCommandInteraction) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut options = &interaction.data.options;
+
+        // Only SubCommands and SubCommandGroups are sent without a value
+        while let Some(option) = options.first().filter(|o| o.value.is_none()) {
+            path.push(option.name.as_str());
+            options = &option.options;
+        }
+
+        path
+    }
+
     fn get_arguments_from_interaction(
         interaction: &ApplicationCommandInteraction,
     ) -> Vec<CommandDataOption> {
@@ -266,6 +281,39 @@ impl Argument {
     pub fn parse_message(
         content: &str,
         tree: &CommandArgumentsTree,
+    ) -> Option<(HashMap<String, Self>, CommandFunction)> {
+        Self::parse_message_with_case(content, tree, false)
+    }
+
+    /// Same as [parse_message](Argument::parse_message) but can match subcommand names and aliases ignoring case
+    /// ```
+    /// # use slashy::argument::Argument;
+    /// # use slashy::commands::*;
+    /// # use slashy::framework::CommandContext;
+    /// # use slashy::subcommand;
+    /// # #[subcommand]
+    /// # async fn points(_ctx: &CommandContext) -> CommandResult {Ok(())}
+    /// let tree = CommandArgumentsTree {
+    ///     children: Some(vec![CommandArguments::SubCommand {
+    ///         name: "points",
+    ///         description: "get a user's points",
+    ///         required: false,
+    ///         options: None,
+    ///         func: Some(points),
+    ///         aliases: Some(vec!["pts"]),
+    ///         cooldown: None,
+    ///     }]),
+    ///     func: None,
+    /// };
+    ///
+    /// assert!(Argument::parse_message_with_case("stats POINTS", &tree, true).is_some());
+    /// assert!(Argument::parse_message_with_case("stats Pts", &tree, true).is_some());
+    /// assert!(Argument::parse_message_with_case("stats POINTS", &tree, false).is_none());
+    /// ```
+    pub fn parse_message_with_case(
+        content: &str,
+        tree: &CommandArgumentsTree,
+        case_insensitive: bool,
     ) -> Option<(HashMap<String, Self>, CommandFunction)> {
         let func = tree.func;
 
@@ -281,14 +329,19 @@ impl Argument {
         let mut args = HashMap::new();
 
         match &tree.children {
-            Some(children) =>
-                match Self::parse_str(&mut str_args_iter, children, &mut args, func) {
-                    Some(f) => match tree.func {
-                        Some(f) => Some((args, f)),
-                        None => Some((args, f)),
-                    },
-                    None => tree.func.map(|f| (HashMap::new(), f)),
+            Some(children) => match Self::parse_str(
+                &mut str_args_iter,
+                children,
+                &mut args,
+                func,
+                case_insensitive,
+            ) {
+                Some(f) => match tree.func {
+                    Some(f) => Some((args, f)),
+                    None => Some((args, f)),
                 },
+                None => tree.func.map(|f| (HashMap::new(), f)),
+            },
             None => func.map(|f| (args, f)),
         }
     }
@@ -298,11 +351,12 @@ impl Argument {
         branch: &Vec<CommandArguments>,
         map: &mut HashMap<String, Self>,
         func: Option<CommandFunction>,
+        case_insensitive: bool,
     ) -> Option<CommandFunction> {
         let mut end_func = func;
         for argument in branch {
             arg_message!(
-                str_args, branch, map, end_func, argument,
+                str_args, branch, map, end_func, argument, case_insensitive,
                 String, String, parse_string;
                 Integer, Integer, parse_int;
                 Boolean, Bool, parse_bool;
@@ -353,6 +407,133 @@ impl ToString for Argument {
     }
 }
 
+/// A type that can be read from a parsed [Argument]
+///
+/// Used by [SlashyArgs](trait@SlashyArgs) for the type of each field.
+pub trait FromArgument: Sized {
+    /// Creates the [CommandArguments] for an argument of this type
+    fn command_argument(
+        name: &'static str,
+        description: &'static str,
+        required: bool,
+    ) -> CommandArguments;
+    /// Reads the value from a parsed argument, `None` if the argument is a different type
+    fn from_argument(arg: &Argument) -> Option<Self>;
+}
+
+macro_rules! from_argument {
+    ($($ty: ty, $arg_type: ident $(, $field: ident)*);*) => {
+        $(impl FromArgument for $ty {
+            fn command_argument(
+                name: &'static str,
+                description: &'static str,
+                required: bool,
+            ) -> CommandArguments {
+                CommandArguments::$arg_type {
+                    name,
+                    description,
+                    required,
+                    $($field: None,)*
+                }
+            }
+
+            fn from_argument(arg: &Argument) -> Option<Self> {
+                match arg {
+                    Argument::$arg_type(v) => Some(v.clone().into()),
+                    _ => None,
+                }
+            }
+        })*
+    };
+}
+
+from_argument! {
+    String, String, choices;
+    i32, Integer, choices, min_value, max_value;
+    i64, Integer, choices, min_value, max_value;
+    bool, Boolean;
+    UserId, User;
+    ChannelId, Channel;
+    RoleId, Role
+}
+
+/// A fieldless enum whose variants are the choices of a String or Integer argument
+///
+/// Derive it with `#[derive(SlashyChoice)]`, this also implements [FromArgument] so the enum can be used in
+/// [SlashyArgs](trait@SlashyArgs) and command functions, or read with [get_choice_arg](crate::framework::CommandContext::get_choice_arg).
+/// ```
+/// # use slashy::{argument::SlashyChoice, SlashyChoice};
+/// #[derive(SlashyChoice, Debug, PartialEq)]
+/// enum Size {
+///     Small,
+///     #[choice(name = "Extra large", value = "xl")]
+///     ExtraLarge,
+/// }
+///
+/// assert_eq!(Size::from_value("small"), Some(Size::Small));
+/// assert_eq!(Size::ExtraLarge.value(), "xl");
+/// assert_eq!(Size::choices()[1].name, "Extra large");
+///
+/// // Integer values make the choices of an Integer argument
+/// #[derive(SlashyChoice, Debug, PartialEq)]
+/// enum Grid {
+///     Small = 1,
+///     #[choice(name = "medium", value = 5)]
+///     Medium,
+/// }
+///
+/// assert_eq!(Grid::from_value(&5), Some(Grid::Medium));
+/// assert_eq!(*Grid::Small.value(), 1);
+/// ```
+pub trait SlashyChoice: FromArgument {
+    /// The type of the values, `str` for the choices of a String argument or `i32` for an Integer argument
+    type Value: ?Sized + ToOwned + 'static;
+    /// The choices sent to discord, one for each variant
+    fn choices() -> Vec<ArgumentChoice<<Self::Value as ToOwned>::Owned>>;
+    /// Gets the variant with a value, `None` if no variant has it
+    fn from_value(value: &Self::Value) -> Option<Self>;
+    /// The value of the variant
+    fn value(&self) -> &'static Self::Value;
+}
+
+/// A struct that the arguments of a command are read into
+///
+/// Derive it with `#[derive(SlashyArgs)]` and add its arguments to a command with the `args(Type)` modifier of [command](crate::command),
+/// so the command and the struct its function reads can't disagree.
+/// ```
+/// # use slashy::{command, commands::CommandResult, framework::CommandContext, subcommand, SlashyArgs};
+/// # use slashy::serenity::model::id::UserId;
+/// #[derive(SlashyArgs)]
+/// struct BanArgs {
+///     #[arg(description = "the user to ban")]
+///     user: UserId,
+///     #[arg(description = "why they were banned")]
+///     reason: Option<String>,
+/// }
+///
+/// command! {
+///     ban,
+///     "bans a user",
+///     ban,
+///     args(BanArgs)
+/// }
+///
+/// #[subcommand]
+/// async fn ban(ctx: &CommandContext) -> CommandResult {
+///     let args = ctx.parse_args::<BanArgs>()?;
+///     ctx.send_str(&format!("Banned {} for {:?}", args.user, args.reason)).await?;
+///     Ok(())
+/// }
+/// ```
+pub trait SlashyArgs: Sized {
+    /// The arguments of each field, in the order they are declared
+    fn arguments() -> Vec<CommandArguments>;
+    /// Reads the struct from the parsed arguments
+    ///
+    /// Errors if a required argument is missing or an argument has an invalid value
+    fn from_args(args: &HashMap<String, Argument>) -> Result<Self, SlashyError>;
+}
+
 #[test]
 fn str_split_test() {
     let test1 = "this is a test!";
@@ -406,12 +587,16 @@ fn str_parse_test() {
                 name: "get",
                 description: "",
                 required: false,
+                aliases: None,
+                cooldown: None,
                 func: None,
                 options: Some(vec![
                     CommandArguments::SubCommand {
                         name: "points",
                         description: "get a user's points",
                         required: false,
+                        aliases: None,
+                        cooldown: None,
                         func: Some(test),
                         options: Some(vec![CommandArguments::User {
                             name: "user",
@@ -423,6 +608,8 @@ fn str_parse_test() {
                         name: "leaderboard",
                         description: "get the guild leaderboard",
                         required: false,
+                        aliases: None,
+                        cooldown: None,
                         func: Some(test2),
                         options: Some(vec![CommandArguments::Integer {
                             name: "page",
@@ -432,6 +619,8 @@ fn str_parse_test() {
                                 name: "default",
                                 value: 0,
                             }]),
+                            min_value: None,
+                            max_value: None,
                         }]),
                     },
                 ]),
@@ -440,6 +629,8 @@ fn str_parse_test() {
                 name: "self",
                 description: "get your personal stats",
                 required: false,
+                aliases: None,
+                cooldown: None,
                 options: None,
                 func: Some(test3),
             },
@@ -469,3 +660,45 @@ fn str_parse_test() {
 
     assert!(get.is_none());
 }
+
+#[test]
+fn str_parse_alias_test() {
+    use crate::{
+        commands::{CommandArguments, CommandArgumentsTree, CommandResult},
+        framework::CommandContext,
+    };
+    use serenity::futures::future::{BoxFuture, FutureExt};
+    fn test(_ctx: &CommandContext) -> BoxFuture<'_, CommandResult> {
+        async move { Ok(()) }.boxed()
+    }
+
+    let arguments_tree = CommandArgumentsTree {
+        children: Some(vec![CommandArguments::SubCommandGroup {
+            name: "get",
+            description: "",
+            required: false,
+            func: None,
+            options: Some(vec![CommandArguments::SubCommand {
+                name: "points",
+                description: "get a user's points",
+                required: false,
+                func: Some(test),
+                options: None,
+                aliases: Some(vec!["pts", "score"]),
+                cooldown: None,
+            }]),
+            aliases: Some(vec!["g"]),
+            cooldown: None,
+        }]),
+        func: None,
+    };
+
+    assert!(Argument::parse_message("test get points", &arguments_tree).is_some());
+    assert!(Argument::parse_message("test g score", &arguments_tree).is_some());
+    assert!(Argument::parse_message("test GET points", &arguments_tree).is_none());
+    assert!(Argument::parse_message("test get Pts", &arguments_tree).is_none());
+
+    assert!(Argument::parse_message_with_case("test GET points", &arguments_tree, true).is_some());
+    assert!(Argument::parse_message_with_case("test G PTS", &arguments_tree, true).is_some());
+    assert!(Argument::parse_message_with_case("test get pointz", &arguments_tree, true).is_none());
+}
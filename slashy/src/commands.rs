@@ -1,12 +1,21 @@
 use std::{error::Error, fmt::Display};
 
 use serde_json::json;
-use serenity::futures::future::BoxFuture;
+use serenity::{
+    client::Context,
+    futures::future::BoxFuture,
+    model::{channel::Channel, guild::Member, id::GuildId},
+};
 
 
 use serde::Serialize;
 
-use crate::framework::CommandContext;
+use crate::{
+    concurrency::MaxConcurrency,
+    cooldown::Cooldown,
+    framework::CommandContext,
+    permissions::PermissionsCheck,
+};
 
 /// A command
 #[derive(Serialize)]
@@ -18,10 +27,281 @@ pub struct Command {
     /// The tree of arguments
     #[serde(flatten)]
     pub arguments_tree: CommandArgumentsTree,
+    /// The category the command is listed under in help
+    #[serde(skip)]
+    pub category: Option<&'static str>,
+    /// The name of the [CommandGroup] whose checks have to pass before the command is run
+    #[serde(skip)]
+    pub group: Option<&'static str>,
+    /// Limits how often the command can be used
+    #[serde(skip)]
+    pub cooldown: Option<Cooldown>,
+    /// Limits how many uses of the command can run at once
+    #[serde(skip)]
+    pub max_concurrency: Option<MaxConcurrency>,
+    /// Where the command is registered and can be used
+    #[serde(skip)]
+    pub scope: CommandScope,
+}
+
+/// Where a command is registered and can be used
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum CommandScope {
+    /// Registered globally and usable everywhere
+    #[default]
+    Global,
+    /// Only registered to and usable in these guilds
+    Guilds(Vec<GuildId>),
+    /// Only registered to and usable in the guilds given by [SettingsProvider::command_guilds](crate::settings::SettingsProvider::command_guilds)
+    Dynamic,
+}
+
+impl Command {
+    /// Renders the canonical usage line of the command
+    ///
+    /// Required arguments are shown as `<name>`, optional ones as `[name]` and SubCommands as `a|b`
+    /// ```
+    /// # use slashy::commands::*;
+    /// # use slashy::framework::CommandContext;
+    /// # use slashy::subcommand;
+    /// # #[subcommand]
+    /// # async fn grid(_ctx: &CommandContext) -> CommandResult {Ok(())}
+    /// let cmd = Command {
+    ///     name: "grid",
+    ///     description: "prints a grid",
+    ///     arguments_tree: CommandArgumentsTree {
+    ///         children: Some(vec![
+    ///             CommandArguments::Integer {
+    ///                 name: "size",
+    ///                 description: "the size of the grid",
+    ///                 required: true,
+    ///                 choices: Some(vec![
+    ///                     ArgumentChoice { name: "small", value: 1 },
+    ///                     ArgumentChoice { name: "large", value: 12 },
+    ///                 ]),
+    ///                 min_value: None,
+    ///                 max_value: None,
+    ///             },
+    ///             CommandArguments::String {
+    ///                 name: "fill",
+    ///                 description: "the character to fill with",
+    ///                 required: false,
+    ///                 choices: None,
+    ///             },
+    ///         ]),
+    ///         func: Some(grid),
+    ///     },
+    ///     category: None,
+    ///     group: None,
+    ///     cooldown: None,
+    ///     max_concurrency: None,
+    ///     scope: CommandScope::Global,
+    /// };
+    ///
+    /// assert_eq!(cmd.usage("!"), "!grid <size: 1|12> [fill]");
+    /// ```
+    pub fn usage(&self, prefix: &str) -> String {
+        self.arguments_tree.usage(&format!("{prefix}{}", self.name))
+    }
+
+    /// Renders the usage line of the subcommand the user was trying to run
+    ///
+    /// `words` are the arguments given after the command name, for a text command these are the words of the message.<br>
+    /// As many of the words as possible are matched against subcommand names and the usage of the deepest match is rendered.
+    pub fn usage_for(&self, prefix: &str, words: &[&str], case_insensitive: bool) -> String {
+        let path = self.arguments_tree.find_path(words, case_insensitive);
+
+        let mut base = format!("{prefix}{}", self.name);
+        for node in &path {
+            base.push(' ');
+            base.push_str(node.name());
+        }
+
+        match path.last() {
+            Some(
+                CommandArguments::SubCommand { options, func, .. }
+                | CommandArguments::SubCommandGroup { options, func, .. },
+            ) => node_usage(base, func.is_some(), options.as_ref()),
+            _ => self.arguments_tree.usage(&base),
+        }
+    }
+
+    /// Renders a usage line for every runnable path through the command
+    ///
+    /// ```
+    /// # use slashy::commands::*;
+    /// # use slashy::framework::CommandContext;
+    /// # use slashy::subcommand;
+    /// # #[subcommand]
+    /// # async fn points(_ctx: &CommandContext) -> CommandResult {Ok(())}
+    /// let cmd = Command {
+    ///     name: "stats",
+    ///     description: "get a user's stats",
+    ///     arguments_tree: CommandArgumentsTree {
+    ///         children: Some(vec![CommandArguments::SubCommand {
+    ///             name: "points",
+    ///             description: "get a user's points",
+    ///             required: false,
+    ///             options: Some(vec![CommandArguments::User {
+    ///                 name: "user",
+    ///                 description: "the user whose points you want",
+    ///                 required: true,
+    ///             }]),
+    ///             func: Some(points),
+    ///             aliases: None,
+    ///             cooldown: None,
+    ///         }]),
+    ///         func: None,
+    ///     },
+    ///     category: None,
+    ///     group: None,
+    ///     cooldown: None,
+    ///     max_concurrency: None,
+    ///     scope: CommandScope::Global,
+    /// };
+    ///
+    /// assert_eq!(cmd.usage_lines("!"), vec!["!stats points <user>"]);
+    /// ```
+    pub fn usage_lines(&self, prefix: &str) -> Vec<String> {
+        let mut lines = Vec::new();
+        push_usage_lines(
+            format!("{prefix}{}", self.name),
+            self.arguments_tree.func.is_some(),
+            self.arguments_tree.children.as_ref(),
+            &mut lines,
+        );
+        lines
+    }
+}
+
+/// Renders `base` followed by the arguments of a single node, leaving out its subcommands
+fn arguments_usage(base: String, children: &[CommandArguments]) -> String {
+    let mut line = base;
+    for arg in children.iter().filter(|a| !a.is_subcommand()) {
+        line.push(' ');
+        line.push_str(&arg.usage());
+    }
+    line
+}
+
+/// Renders the arguments of a single node followed by the choice of its subcommands
+fn node_usage(base: String, runnable: bool, children: Option<&Vec<CommandArguments>>) -> String {
+    let children = children.map(Vec::as_slice).unwrap_or_default();
+    let mut line = arguments_usage(base, children);
+
+    let subcommands = children
+        .iter()
+        .filter(|a| a.is_subcommand())
+        .map(CommandArguments::name)
+        .collect::<Vec<_>>();
+
+    if !subcommands.is_empty() {
+        // If the node can't run by itself one of the subcommands has to be picked
+        if runnable {
+            line.push_str(&format!(" [{}]", subcommands.join("|")));
+        } else {
+            line.push_str(&format!(" <{}>", subcommands.join("|")));
+        }
+    }
+
+    line
+}
+
+/// Adds the usage of a node and then recurses into its subcommands
+fn push_usage_lines(
+    base: String,
+    runnable: bool,
+    children: Option<&Vec<CommandArguments>>,
+    lines: &mut Vec<String>,
+) {
+    let children = children.map(Vec::as_slice).unwrap_or_default();
+
+    if runnable {
+        lines.push(arguments_usage(base.clone(), children));
+    }
+
+    for child in children {
+        if let CommandArguments::SubCommand {
+            name,
+            options,
+            func,
+            ..
+        }
+        | CommandArguments::SubCommandGroup {
+            name,
+            options,
+            func,
+            ..
+        } = child
+        {
+            push_usage_lines(
+                format!("{base} {name}"),
+                func.is_some(),
+                options.as_ref(),
+                lines,
+            );
+        }
+    }
+}
+
+/// A group of commands that share checks
+///
+/// Groups are registered with [GroupInit](crate::framework::GroupInit) and are used as the category of their commands.<br>
+/// Commands registered on their own are part of a group if they name it with `group("name")` in [command!](crate::command).
+pub struct CommandGroup {
+    /// The name of the group
+    pub name: &'static str,
+    /// Description of the group
+    pub description: &'static str,
+    /// Permission checks that have to pass before any command in the group is run
+    pub checks: Vec<PermissionsCheck>,
+    /// Whether the commands in the group can be run in dms
+    pub works_in_dms: bool,
+    /// The commands in the group
+    pub commands: Vec<Command>,
+}
+
+impl CommandGroup {
+    /// Runs the group's checks against the user and channel a command was triggered in
+    pub async fn check(&self, ctx: &CommandContext) -> CommandResult {
+        let channel = ctx.channel().await?;
+        let member = match channel {
+            Channel::Guild(_) => Some(ctx.member().await?),
+            _ => None,
+        };
+        self.check_in(&ctx.ctx, &channel, member.as_ref()).await
+    }
+
+    /// Runs the group's checks against a channel and, for guild channels, the member who used the command
+    pub async fn check_in(
+        &self,
+        ctx: &Context,
+        channel: &Channel,
+        member: Option<&Member>,
+    ) -> CommandResult {
+        match channel {
+            Channel::Guild(channel) => {
+                let member =
+                    member.ok_or_else(|| SlashyError::new("Command was used without a member"))?;
+                for check in &self.checks {
+                    if !check(ctx, member, channel).await? {
+                        return Err(Box::new(SlashyError::new("User does not have permissions")));
+                    }
+                }
+                Ok(())
+            }
+            _ if self.works_in_dms => Ok(()),
+            _ => Err(Box::new(SlashyError::new(
+                "Command is not available in dms",
+            ))),
+        }
+    }
 }
 
 /// A function run for a command
 pub type CommandFunction = fn(&CommandContext) -> BoxFuture<CommandResult>;
+/// A function run when a command returns an error
+pub type ErrorHandler = for<'a> fn(&'a CommandContext, CommandError) -> BoxFuture<'a, ()>;
 /// The return type of CommandFunction
 pub type CommandResult<T = ()> = Result<T, CommandError>;
 /// Variable error type for commands
@@ -62,6 +342,43 @@ pub struct CommandArgumentsTree {
     pub func: Option<CommandFunction>,
 }
 
+impl CommandArgumentsTree {
+    /// Renders the usage line of the root of the tree
+    ///
+    /// `base` is everything that comes before the arguments, normally the prefix and command name
+    pub fn usage(&self, base: &str) -> String {
+        node_usage(base.to_owned(), self.func.is_some(), self.children.as_ref())
+    }
+
+    /// Finds the SubCommands and SubCommandGroups named by `words`
+    ///
+    /// Matching stops at the first word that doesn't name a subcommand of the last matched node
+    pub fn find_path(&self, words: &[&str], case_insensitive: bool) -> Vec<&CommandArguments> {
+        let mut path = Vec::new();
+        let mut children = self.children.as_ref();
+
+        for word in words {
+            let next = children
+                .into_iter()
+                .flatten()
+                .find(|c| c.is_subcommand() && c.matches_name(word, case_insensitive));
+
+            match next {
+                Some(
+                    node @ (CommandArguments::SubCommand { options, .. }
+                    | CommandArguments::SubCommandGroup { options, .. }),
+                ) => {
+                    path.push(node);
+                    children = options.as_ref();
+                }
+                _ => break,
+            }
+        }
+
+        path
+    }
+}
+
 /// The argument metadata we store with the command
 ///
 /// These are used to parse text / interaction responses into [Arguments](crate::argument::Argument)
@@ -73,6 +390,10 @@ pub enum CommandArguments {
         required: bool,
         options: Option<Vec<CommandArguments>>,
         func: Option<CommandFunction>,
+        /// Extra names accepted for this node in text commands, never sent to discord
+        aliases: Option<Vec<&'static str>>,
+        /// Limits how often this node can be used, on top of any cooldown of the command
+        cooldown: Option<Cooldown>,
     },
     SubCommandGroup {
         name: &'static str,
@@ -80,6 +401,10 @@ pub enum CommandArguments {
         required: bool,
         options: Option<Vec<CommandArguments>>,
         func: Option<CommandFunction>,
+        /// Extra names accepted for this node in text commands, never sent to discord
+        aliases: Option<Vec<&'static str>>,
+        /// Limits how often this node can be used, on top of any cooldown of the command
+        cooldown: Option<Cooldown>,
     },
     String {
         name: &'static str,
@@ -92,6 +417,10 @@ pub enum CommandArguments {
         description: &'static str,
         required: bool,
         choices: Option<Vec<ArgumentChoice<i32>>>,
+        /// The smallest value discord lets the user give
+        min_value: Option<i64>,
+        /// The largest value discord lets the user give
+        max_value: Option<i64>,
     },
     Boolean {
         name: &'static str,
@@ -116,6 +445,122 @@ pub enum CommandArguments {
 }
 
 
+impl CommandArguments {
+    /// The name of the argument
+    pub fn name(&self) -> &'static str {
+        match self {
+            CommandArguments::SubCommand { name, .. }
+            | CommandArguments::SubCommandGroup { name, .. }
+            | CommandArguments::String { name, .. }
+            | CommandArguments::Integer { name, .. }
+            | CommandArguments::Boolean { name, .. }
+            | CommandArguments::User { name, .. }
+            | CommandArguments::Channel { name, .. }
+            | CommandArguments::Role { name, .. } => name,
+        }
+    }
+
+    /// The description of the argument
+    pub fn description(&self) -> &'static str {
+        match self {
+            CommandArguments::SubCommand { description, .. }
+            | CommandArguments::SubCommandGroup { description, .. }
+            | CommandArguments::String { description, .. }
+            | CommandArguments::Integer { description, .. }
+            | CommandArguments::Boolean { description, .. }
+            | CommandArguments::User { description, .. }
+            | CommandArguments::Channel { description, .. }
+            | CommandArguments::Role { description, .. } => description,
+        }
+    }
+
+    /// Whether the argument is required
+    pub fn required(&self) -> bool {
+        match self {
+            CommandArguments::SubCommand { required, .. }
+            | CommandArguments::SubCommandGroup { required, .. }
+            | CommandArguments::String { required, .. }
+            | CommandArguments::Integer { required, .. }
+            | CommandArguments::Boolean { required, .. }
+            | CommandArguments::User { required, .. }
+            | CommandArguments::Channel { required, .. }
+            | CommandArguments::Role { required, .. } => *required,
+        }
+    }
+
+    /// Checks if a word given in a text command refers to this argument by its name or one of its aliases
+    ///
+    /// Only SubCommands and SubCommandGroups can have aliases
+    pub fn matches_name(&self, word: &str, case_insensitive: bool) -> bool {
+        let matches = |n: &str| {
+            if case_insensitive {
+                n.eq_ignore_ascii_case(word)
+            } else {
+                n == word
+            }
+        };
+
+        let aliases = match self {
+            CommandArguments::SubCommand { aliases, .. }
+            | CommandArguments::SubCommandGroup { aliases, .. } => aliases.as_ref(),
+            _ => None,
+        };
+
+        matches(self.name()) || aliases.into_iter().flatten().any(|a| matches(a))
+    }
+
+    /// The cooldown of a SubCommand or SubCommandGroup
+    pub fn cooldown(&self) -> Option<Cooldown> {
+        match self {
+            CommandArguments::SubCommand { cooldown, .. }
+            | CommandArguments::SubCommandGroup { cooldown, .. } => *cooldown,
+            _ => None,
+        }
+    }
+
+    /// Whether the argument is a SubCommand or SubCommandGroup
+    pub fn is_subcommand(&self) -> bool {
+        matches!(
+            self,
+            CommandArguments::SubCommand { .. } | CommandArguments::SubCommandGroup { .. }
+        )
+    }
+
+    /// The values that can be given for the argument, if it has choices
+    pub fn choice_values(&self) -> Option<Vec<String>> {
+        match self {
+            CommandArguments::String {
+                choices: Some(c), ..
+            } => Some(c.iter().map(|c| c.value.clone()).collect()),
+            CommandArguments::Integer {
+                choices: Some(c), ..
+            } => Some(c.iter().map(|c| c.value.to_string()).collect()),
+            _ => None,
+        }
+    }
+
+    /// Renders the argument as it would appear in a usage string
+    ///
+    /// Required arguments are shown as `<name>` and optional ones as `[name]`, with any choices listed after the name.<br>
+    /// SubCommands are typed out literally so they are shown as just their name.
+    pub fn usage(&self) -> String {
+        if self.is_subcommand() {
+            return self.name().to_owned();
+        }
+
+        let inner = match self.choice_values() {
+            Some(values) => format!("{}: {}", self.name(), values.join("|")),
+            None => self.name().to_owned(),
+        };
+
+        if self.required() {
+            format!("<{inner}>")
+        } else {
+            format!("[{inner}]")
+        }
+    }
+}
+
 macro_rules! command_options_serialize {
     ($self: ident, $map: ident, $($val: path, $type_val: expr, $( $i:ident),* | $($i1:ident),*);*) => {
         match $self {
@@ -142,7 +587,7 @@ impl Serialize for CommandArguments {
             CommandArguments::SubCommand, 1, description, name, required | options;
             CommandArguments::SubCommandGroup, 2, name, description, required | options;
             CommandArguments::String, 3, name, description, required| choices;
-            CommandArguments::Integer, 4, name, description, required| choices;
+            CommandArguments::Integer, 4, name, description, required| choices, min_value, max_value;
             CommandArguments::Boolean, 5, name, description, required|;
             CommandArguments::User, 6, name, description, required|;
             CommandArguments::Channel, 7, name, description, required|;
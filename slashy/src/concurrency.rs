@@ -0,0 +1,165 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::Display,
+    sync::{Arc, Mutex as StdMutex},
+    time::Instant,
+};
+
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+
+use crate::cooldown::{BucketType, SWEEP_INTERVAL};
+
+/// What to do when a command is run while it is already running as many times as it can
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConcurrencyMode {
+    /// Fail with a [ConcurrencyError]
+    Reject,
+    /// Wait until one of the running uses finishes
+    Queue,
+}
+
+/// Limits how many uses of a command can be running at once
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxConcurrency {
+    /// How many uses can run at once
+    pub limit: u32,
+    /// What uses are counted together
+    pub bucket: BucketType,
+    /// What to do when the limit is reached
+    pub mode: ConcurrencyMode,
+}
+
+/// The error returned when a command is already running as many times as it can
+#[derive(Debug)]
+pub struct ConcurrencyError {
+    /// How many uses can run at once
+    pub limit: u32,
+    /// The bucket that is at its limit
+    pub bucket: BucketType,
+}
+
+impl Error for ConcurrencyError {}
+
+impl Display for ConcurrencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "This command can only run {} at a time, try again later",
+            self.limit
+        )
+    }
+}
+
+/// Keeps track of how many uses of each command are running
+#[derive(Default)]
+pub(crate) struct ConcurrencyTracker {
+    semaphores: Mutex<HashMap<(&'static str, u64), Arc<Semaphore>>>,
+    last_sweep: StdMutex<Option<Instant>>,
+}
+
+impl ConcurrencyTracker {
+    /// Takes a slot for a use of `command` in `bucket_id`
+    ///
+    /// The slot is given back when the returned permit is dropped
+    pub(crate) async fn acquire(
+        &self,
+        command: &'static str,
+        bucket_id: u64,
+        max: &MaxConcurrency,
+    ) -> Result<OwnedSemaphorePermit, ConcurrencyError> {
+        let semaphore = {
+            let mut semaphores = self.semaphores.lock().await;
+            self.sweep(&mut semaphores, Instant::now());
+            semaphores
+                .entry((command, bucket_id))
+                .or_insert_with(|| Arc::new(Semaphore::new(max.limit as usize)))
+                .clone()
+        };
+
+        let error = ConcurrencyError {
+            limit: max.limit,
+            bucket: max.bucket,
+        };
+        match max.mode {
+            ConcurrencyMode::Reject => semaphore.try_acquire_owned().map_err(|_| error),
+            // The semaphore is never closed so this can't fail
+            ConcurrencyMode::Queue => semaphore.acquire_owned().await.map_err(|_| error),
+        }
+    }
+
+    /// Drops the semaphores of idle buckets, at most once every [SWEEP_INTERVAL]
+    fn sweep(&self, semaphores: &mut HashMap<(&'static str, u64), Arc<Semaphore>>, now: Instant) {
+        let mut last_sweep = self.last_sweep.lock().unwrap();
+        if last_sweep.is_none_or(|t| now.duration_since(t) >= SWEEP_INTERVAL) {
+            // Permits and waiters hold a reference to the semaphore, so if only the map does the bucket is idle
+            semaphores.retain(|_, s| Arc::strong_count(s) > 1);
+            *last_sweep = Some(now);
+        }
+    }
+}
+
+#[tokio::test]
+async fn concurrency_reject_test() {
+    let tracker = ConcurrencyTracker::default();
+    let max = MaxConcurrency {
+        limit: 2,
+        bucket: BucketType::Guild,
+        mode: ConcurrencyMode::Reject,
+    };
+
+    let first = tracker.acquire("render", 1, &max).await.unwrap();
+    let _second = tracker.acquire("render", 1, &max).await.unwrap();
+    let err = tracker.acquire("render", 1, &max).await.unwrap_err();
+    assert_eq!(err.limit, 2);
+    assert_eq!(err.bucket, BucketType::Guild);
+
+    // Other guilds have their own slots
+    assert!(tracker.acquire("render", 2, &max).await.is_ok());
+
+    drop(first);
+    assert!(tracker.acquire("render", 1, &max).await.is_ok());
+}
+
+#[tokio::test]
+async fn concurrency_cleanup_test() {
+    let tracker = ConcurrencyTracker::default();
+    let max = MaxConcurrency {
+        limit: 1,
+        bucket: BucketType::User,
+        mode: ConcurrencyMode::Reject,
+    };
+
+    let first = tracker.acquire("render", 1, &max).await.unwrap();
+    drop(tracker.acquire("render", 2, &max).await.unwrap());
+    let _third = tracker.acquire("render", 3, &max).await.unwrap();
+
+    // Idle buckets are only dropped once the sweep interval has passed
+    let mut semaphores = tracker.semaphores.lock().await;
+    assert!(semaphores.contains_key(&("render", 2)));
+
+    // Bucket 2 has no permits held so it was dropped, bucket 1 is still running
+    tracker.sweep(&mut semaphores, Instant::now() + SWEEP_INTERVAL);
+    assert!(semaphores.contains_key(&("render", 1)));
+    assert!(!semaphores.contains_key(&("render", 2)));
+    drop(first);
+}
+
+#[tokio::test]
+async fn concurrency_queue_test() {
+    use serenity::futures::FutureExt;
+
+    let tracker = ConcurrencyTracker::default();
+    let max = MaxConcurrency {
+        limit: 1,
+        bucket: BucketType::Global,
+        mode: ConcurrencyMode::Queue,
+    };
+
+    let first = tracker.acquire("render", 0, &max).await.unwrap();
+    let mut queued = Box::pin(tracker.acquire("render", 0, &max));
+    assert!((&mut queued).now_or_never().is_none());
+
+    drop(first);
+    assert!(queued.await.is_ok());
+}
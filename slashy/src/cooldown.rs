@@ -0,0 +1,241 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    error::Error,
+    fmt::Display,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::framework::CommandContext;
+
+/// What uses of a command are counted together
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BucketType {
+    /// Each user has their own count
+    User,
+    /// Each channel has its own count
+    Channel,
+    /// Each guild has its own count, dms are counted per channel
+    Guild,
+    /// Every use is counted together
+    Global,
+}
+
+impl BucketType {
+    /// Gets the id of the bucket a command use falls into
+    pub fn id(&self, ctx: &CommandContext) -> u64 {
+        match self {
+            BucketType::User => ctx.author_id().0,
+            BucketType::Channel => ctx.channel_id().0,
+            BucketType::Guild => ctx
+                .guild_id()
+                .map(|g| g.0)
+                .unwrap_or_else(|| ctx.channel_id().0),
+            BucketType::Global => 0,
+        }
+    }
+}
+
+/// Limits a command to a number of uses in a window of time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cooldown {
+    /// How many times the command can be used in the window
+    pub uses: u32,
+    /// The length of the window
+    pub per: Duration,
+    /// What uses are counted together
+    pub bucket: BucketType,
+}
+
+/// The error returned when a command is run while on cooldown
+#[derive(Debug)]
+pub struct CooldownError {
+    /// How long until the command can be used again
+    pub remaining: Duration,
+    /// The bucket that is on cooldown
+    pub bucket: BucketType,
+}
+
+impl Error for CooldownError {}
+
+impl Display for CooldownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Round up so we never tell someone to try again before they can
+        let secs = self.remaining.as_secs() + u64::from(self.remaining.subsec_nanos() > 0);
+        write!(f, "This command is on cooldown, try again in {secs}s")
+    }
+}
+
+/// How often uses that have left their cooldown's window, and idle concurrency buckets, are removed
+pub(crate) const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Keeps track of when commands were used to enforce their cooldowns
+#[derive(Default)]
+pub(crate) struct CooldownTracker {
+    uses: Mutex<HashMap<(String, u64), Uses>>,
+    last_sweep: Mutex<Option<Instant>>,
+}
+
+/// The recent uses in one bucket of a cooldown
+#[derive(Default)]
+struct Uses {
+    /// The length of the cooldown's window
+    per: Duration,
+    times: VecDeque<Instant>,
+}
+
+impl CooldownTracker {
+    /// Records a use against every cooldown in `hits`
+    ///
+    /// Each hit is the key of the cooldown, the id of the bucket and the cooldown itself.<br>
+    /// Nothing is recorded if any of the cooldowns are exhausted, the longest remaining wait is returned instead.
+    pub(crate) fn hit(
+        &self,
+        hits: &[(String, u64, Cooldown)],
+        now: Instant,
+    ) -> Result<(), CooldownError> {
+        let mut uses = self.uses.lock().unwrap();
+        let mut error: Option<CooldownError> = None;
+
+        // Buckets without uses in their window would otherwise be kept forever
+        let mut last_sweep = self.last_sweep.lock().unwrap();
+        if last_sweep.is_none_or(|t| now.duration_since(t) >= SWEEP_INTERVAL) {
+            uses.retain(|_, u| {
+                u.times
+                    .back()
+                    .is_some_and(|t| now.duration_since(*t) < u.per)
+            });
+            *last_sweep = Some(now);
+        }
+
+        for (key, bucket_id, cooldown) in hits {
+            let entry = uses.entry((key.clone(), *bucket_id)).or_default();
+            entry.per = cooldown.per;
+            let times = &mut entry.times;
+            while let Some(t) = times.front() {
+                if now.duration_since(*t) < cooldown.per {
+                    break;
+                }
+                times.pop_front();
+            }
+
+            if times.len() >= cooldown.uses as usize {
+                let remaining = match times.front() {
+                    Some(t) => cooldown.per - now.duration_since(*t),
+                    None => cooldown.per,
+                };
+
+                match &error {
+                    Some(e) if e.remaining >= remaining => {}
+                    _ =>
+                        error = Some(CooldownError {
+                            remaining,
+                            bucket: cooldown.bucket,
+                        }),
+                }
+            }
+        }
+
+        match error {
+            Some(e) => Err(e),
+            None => {
+                for (key, bucket_id, _) in hits {
+                    uses.entry((key.clone(), *bucket_id))
+                        .or_default()
+                        .times
+                        .push_back(now);
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[test]
+fn cooldown_test() {
+    let tracker = CooldownTracker::default();
+    let cooldown = Cooldown {
+        uses: 2,
+        per: Duration::from_secs(10),
+        bucket: BucketType::User,
+    };
+    let start = Instant::now();
+    let hit = |bucket, secs| {
+        tracker.hit(
+            &[("render".to_owned(), bucket, cooldown)],
+            start + Duration::from_secs(secs),
+        )
+    };
+
+    assert!(hit(1, 0).is_ok());
+    assert!(hit(1, 4).is_ok());
+    assert_eq!(hit(1, 6).unwrap_err().remaining, Duration::from_secs(4));
+    // Other buckets are counted separately
+    assert!(hit(2, 6).is_ok());
+    // The first use has left the window
+    assert!(hit(1, 10).is_ok());
+    assert_eq!(hit(1, 12).unwrap_err().remaining, Duration::from_secs(2));
+}
+
+#[test]
+fn cooldown_nested_test() {
+    let tracker = CooldownTracker::default();
+    let command = Cooldown {
+        uses: 5,
+        per: Duration::from_secs(60),
+        bucket: BucketType::Guild,
+    };
+    let subcommand = Cooldown {
+        uses: 1,
+        per: Duration::from_secs(30),
+        bucket: BucketType::User,
+    };
+    let start = Instant::now();
+    let hits = [
+        ("stats".to_owned(), 1, command),
+        ("stats get".to_owned(), 2, subcommand),
+    ];
+
+    assert!(tracker.hit(&hits, start).is_ok());
+
+    let err = tracker
+        .hit(&hits, start + Duration::from_secs(5))
+        .unwrap_err();
+    assert_eq!(err.remaining, Duration::from_secs(25));
+    assert_eq!(err.bucket, BucketType::User);
+    assert_eq!(
+        err.to_string(),
+        "This command is on cooldown, try again in 25s"
+    );
+
+    // The failed use isn't counted against the command's cooldown
+    let uses = tracker.uses.lock().unwrap();
+    assert_eq!(uses.get(&("stats".to_owned(), 1)).unwrap().times.len(), 1);
+}
+
+#[test]
+fn cooldown_sweep_test() {
+    let tracker = CooldownTracker::default();
+    let cooldown = Cooldown {
+        uses: 1,
+        per: Duration::from_secs(10),
+        bucket: BucketType::User,
+    };
+    let start = Instant::now();
+    let hit = |bucket, secs| {
+        tracker.hit(
+            &[("render".to_owned(), bucket, cooldown)],
+            start + Duration::from_secs(secs),
+        )
+    };
+
+    assert!(hit(1, 0).is_ok());
+    assert!(hit(2, 55).is_ok());
+    assert_eq!(tracker.uses.lock().unwrap().len(), 2);
+
+    // Bucket 1 left its window long ago and is removed, bucket 2 is still on cooldown
+    assert!(hit(3, 60).is_ok());
+    let uses = tracker.uses.lock().unwrap();
+    assert!(!uses.contains_key(&("render".to_owned(), 1)));
+    assert!(uses.contains_key(&("render".to_owned(), 2)));
+}
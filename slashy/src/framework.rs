@@ -1,7 +1,7 @@
 // Allow dead code as the impl of CommandContext is a public facing api and so would mostly be dead in the lib itself
 #![allow(dead_code)]
 
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc, time::Instant};
 
 use serde_json::Value;
 use serenity::{
@@ -39,48 +39,239 @@ use serenity::{
             VoiceState,
         },
     },
+    prelude::RwLock,
     Result,
 };
 
-use crate::{argument::Argument, commands::Command, settings::SettingsProvider};
+use crate::{
+    argument::{Argument, SlashyArgs, SlashyChoice},
+    commands::{
+        Command,
+        CommandFunction,
+        CommandGroup,
+        CommandResult,
+        CommandScope,
+        ErrorHandler,
+        SlashyError,
+    },
+    concurrency::ConcurrencyTracker,
+    cooldown::CooldownTracker,
+    registration::{
+        diff_commands,
+        retry,
+        RegistrationDiff,
+        RegistrationError,
+        RegistrationErrorHandler,
+        RegistrationPlan,
+        RetryPolicy,
+    },
+    settings::{Location, Settings, SettingsProvider},
+    validation::{validate_command, validate_command_set, Violation},
+};
 
 /// The command framework, holds all commands and settings
 pub struct Framework<T: SettingsProvider> {
-    commands: HashMap<&'static str, Command>,
-    settings: T,
+    commands: Arc<HashMap<&'static str, Command>>,
+    groups: HashMap<&'static str, CommandGroup>,
+    settings: Arc<T>,
     /// Stores any additional [EventHandlers](EventHandler) registered
     handlers: Vec<Box<dyn EventHandler>>,
     application_id: u64,
-    registered_command_cache: HashMap<String, CommandId>,
+    /// The ids of the registered global commands, under `None`, and the commands of each guild
+    registered_command_cache: RwLock<HashMap<Option<GuildId>, HashMap<String, CommandId>>>,
+    cooldowns: CooldownTracker,
+    concurrency: ConcurrencyTracker,
+    error_handler: Option<ErrorHandler>,
+    registration_error_handler: Option<RegistrationErrorHandler>,
+    registration_retry: RetryPolicy,
 }
 impl<T: SettingsProvider> Framework<T> {
     /// Creates a new Framework
-    pub async fn new(settings: T, application_id: u64, token: String) -> Self {
+    ///
+    /// Errors if the registered global commands can't be fetched
+    pub async fn new(settings: T, application_id: u64, token: String) -> Result<Self> {
         let http = Http::new_with_application_id(&token, application_id);
-        let registered_command_cache = http
+        let global = http
             .get_global_application_commands()
-            .await
-            .unwrap()
+            .await?
             .iter()
             .map(|a| (a.name.clone(), a.id))
             .collect();
+        let registered_command_cache = RwLock::new(HashMap::from([(None, global)]));
 
-        Framework {
-            commands: HashMap::new(),
-            settings,
+        Ok(Framework {
+            commands: Arc::new(HashMap::new()),
+            groups: HashMap::new(),
+            settings: Arc::new(settings),
             handlers: Vec::new(),
             application_id,
             registered_command_cache,
-        }
+            cooldowns: CooldownTracker::default(),
+            concurrency: ConcurrencyTracker::default(),
+            error_handler: None,
+            registration_error_handler: None,
+            registration_retry: RetryPolicy::default(),
+        })
     }
 
     /// Adds a command
-    pub fn command<C: CommandInit>(mut self) -> Self {
-        let cmd = C::command_init();
-        self.commands.insert(cmd.name, cmd);
+    ///
+    /// Errors if the command names a group that hasn't been added yet
+    pub fn command<C: CommandInit>(mut self) -> std::result::Result<Self, SlashyError> {
+        let mut cmd = C::command_init();
+        if let Some(group) = cmd.group {
+            if !self.groups.contains_key(group) {
+                return Err(SlashyError::new(&format!(
+                    "`{}` is in the group `{group}` which hasn't been added",
+                    cmd.name
+                )));
+            }
+            cmd.category.get_or_insert(group);
+        }
+        self.add_command(cmd);
+        Ok(self)
+    }
+
+    /// Adds a group and all of the commands in it
+    ///
+    /// The commands are put in the group's category.<br>
+    /// Errors if one of the commands names a different group
+    pub fn group<G: GroupInit>(mut self) -> std::result::Result<Self, SlashyError> {
+        let mut group = G::group_init();
+        for mut cmd in group.commands.drain(..) {
+            if let Some(other) = cmd.group.filter(|g| *g != group.name) {
+                return Err(SlashyError::new(&format!(
+                    "`{}` is in the group `{other}` but was added with the group `{}`",
+                    cmd.name, group.name
+                )));
+            }
+            cmd.group = Some(group.name);
+            cmd.category = Some(group.name);
+            self.add_command(cmd);
+        }
+        self.groups.insert(group.name, group);
+        Ok(self)
+    }
+
+    /// Sets the function run when a command returns an error
+    ///
+    /// Without one errors are printed and, in debug builds, sent to the channel the command was run in.
+    /// ```
+    /// # use slashy::commands::CommandError;
+    /// # use slashy::cooldown::CooldownError;
+    /// # use slashy::framework::CommandContext;
+    /// # use slashy::serenity::futures::future::{BoxFuture, FutureExt};
+    /// fn on_error<'a>(ctx: &'a CommandContext, error: CommandError) -> BoxFuture<'a, ()> {
+    ///     async move {
+    ///         let message = match error.downcast_ref::<CooldownError>() {
+    ///             Some(cooldown) => format!("Slow down! {cooldown}"),
+    ///             None => format!("Something went wrong: {error}"),
+    ///         };
+    ///         let _ = ctx.send_str(&message).await;
+    ///     }
+    ///     .boxed()
+    /// }
+    /// ```
+    pub fn error_handler(mut self, handler: ErrorHandler) -> Self {
+        self.error_handler = Some(handler);
         self
     }
 
+    /// Sets the function run when registering slash commands on Ready fails
+    ///
+    /// Without one errors are printed. Commands that did register keep working either way.
+    pub fn registration_error_handler(mut self, handler: RegistrationErrorHandler) -> Self {
+        self.registration_error_handler = Some(handler);
+        self
+    }
+
+    /// Sets how failed registration requests are retried on Ready
+    pub fn registration_retry(mut self, policy: RetryPolicy) -> Self {
+        self.registration_retry = policy;
+        self
+    }
+
+    fn add_command(&mut self, cmd: Command) {
+        // Commands are only shared with CommandContexts once the framework is handling events
+        Arc::get_mut(&mut self.commands)
+            .expect("Commands can only be added before the framework is started")
+            .insert(cmd.name, cmd);
+    }
+
+    /// Runs the checks of everything the command belongs to and then the command's function
+    async fn run_command(
+        &self,
+        cmd: &Command,
+        func: CommandFunction,
+        context: &CommandContext,
+    ) -> CommandResult {
+        let words = context.subcommand_words();
+        let path = cmd
+            .arguments_tree
+            .find_path(&words, self.settings.case_insensitive().await);
+
+        let available = match context.guild_id() {
+            Some(guild_id) => self.in_scope(cmd, guild_id).await,
+            None => cmd.scope == CommandScope::Global,
+        };
+        if !available {
+            return Err(Box::new(SlashyError::new(
+                "This command isn't available here",
+            )));
+        }
+
+        // Commands can only be disabled in guilds
+        if let Some(guild_id) = context.guild_id() {
+            let mut key = cmd.name.to_owned();
+            let mut keys = vec![key.clone()];
+            for node in &path {
+                key.push(' ');
+                key.push_str(node.name());
+                keys.push(key.clone());
+            }
+            for key in keys {
+                if !self
+                    .settings
+                    .command_enabled(guild_id, context.channel_id(), &key)
+                    .await
+                {
+                    return Err(Box::new(SlashyError::new("This command is disabled here")));
+                }
+            }
+        }
+
+        if let Some(group) = cmd.group.and_then(|g| self.groups.get(g)) {
+            group.check(context).await?;
+        }
+
+        // Every node along the path to the function can have its own cooldown
+        let mut key = cmd.name.to_owned();
+        let mut hits = Vec::new();
+        if let Some(cooldown) = cmd.cooldown {
+            hits.push((key.clone(), cooldown.bucket.id(context), cooldown));
+        }
+        for node in path {
+            key.push(' ');
+            key.push_str(node.name());
+            if let Some(cooldown) = node.cooldown() {
+                hits.push((key.clone(), cooldown.bucket.id(context), cooldown));
+            }
+        }
+        self.cooldowns.hit(&hits, Instant::now())?;
+
+        // Held until the function finishes running
+        let _permit = match &cmd.max_concurrency {
+            Some(max) => Some(
+                self.concurrency
+                    .acquire(cmd.name, max.bucket.id(context), max)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        func(context).await
+    }
+
     /// Adds an [EventHandler] to run alongside the framework.
     ///
     /// Needed because Serenity does not allow more than one EventHandler registered at once and the framework uses it for commands.
@@ -89,55 +280,425 @@ impl<T: SettingsProvider> Framework<T> {
         self
     }
 
-    /// Register a Command as a slash command
+    /// Whether a command should be registered globally or to a guild
     ///
-    /// If `guild_id` is `None` then the command is registered globally
-    pub async fn register_slash_command(
+    /// Global commands are registered globally and to every guild in `auto_register_guilds`,
+    /// other commands are only registered to the guilds in their scope.
+    async fn registered_in(&self, cmd: &Command, guild_id: Option<GuildId>) -> bool {
+        match (&cmd.scope, guild_id) {
+            (CommandScope::Global, None) => true,
+            (CommandScope::Global, Some(g)) =>
+                self.settings.auto_register_guilds().await.contains(&g),
+            (_, None) => false,
+            (_, Some(g)) => self.in_scope(cmd, g).await,
+        }
+    }
+
+    /// Whether a command can be used in a guild
+    async fn in_scope(&self, cmd: &Command, guild_id: GuildId) -> bool {
+        match &cmd.scope {
+            CommandScope::Global => true,
+            CommandScope::Guilds(guilds) => guilds.contains(&guild_id),
+            CommandScope::Dynamic => self
+                .settings
+                .command_guilds(cmd.name)
+                .await
+                .contains(&guild_id),
+        }
+    }
+
+    /// Every guild commands are registered to
+    ///
+    /// These are the guilds in `auto_register_guilds`, every guild in a command's scope
+    /// and every guild commands were registered to since the framework started so commands can be removed from guilds that left their scope.<br>
+    /// On Ready every guild the bot is in is also checked to catch guilds that left a scope before a restart.
+    async fn registration_guilds(&self) -> Vec<GuildId> {
+        let mut guilds = self.settings.auto_register_guilds().await;
+        guilds.extend(self.registered_command_cache.read().await.keys().flatten());
+        for cmd in self.commands.values() {
+            match &cmd.scope {
+                CommandScope::Global => {}
+                CommandScope::Guilds(g) => guilds.extend(g),
+                CommandScope::Dynamic =>
+                    guilds.extend(self.settings.command_guilds(cmd.name).await),
+            }
+        }
+        guilds.sort();
+        guilds.dedup();
+        guilds
+    }
+
+    /// Serializes every command to the json sent when registering it, sorted by name
+    ///
+    /// Useful for checking the commands in CI or registering them with another tool.
+    pub fn export_commands(&self) -> serde_json::Result<Vec<Value>> {
+        let mut commands = self.commands.values().collect::<Vec<_>>();
+        commands.sort_by_key(|c| c.name);
+        commands.into_iter().map(serde_json::to_value).collect()
+    }
+
+    /// Checks every command against discord's rules for slash commands without registering them
+    ///
+    /// Each command is checked once, and the limits on how many commands can be registered and duplicate names
+    /// are checked for the global commands and each guild's commands separately, following the scope of each command.<br>
+    /// The paths of those violations start with `global` or `guild {id}`.
+    ///
+    /// Returns every rule that is broken, see [validate_commands](crate::validation::validate_commands).
+    pub async fn validate_commands(&self) -> serde_json::Result<Vec<Violation>> {
+        let mut violations = Vec::new();
+        for cmd in self.export_commands()? {
+            violations.extend(validate_command(&cmd));
+        }
+
+        let guilds = self.registration_guilds().await;
+        for guild_id in std::iter::once(None).chain(guilds.into_iter().map(Some)) {
+            let mut registered = Vec::new();
+            for cmd in self.commands.values() {
+                if self.registered_in(cmd, guild_id).await {
+                    registered.push(serde_json::to_value(cmd)?);
+                }
+            }
+
+            let target = match guild_id {
+                Some(g) => format!("guild {}", g.0),
+                None => "global".to_owned(),
+            };
+            violations.extend(validate_command_set(&registered).into_iter().map(|mut v| {
+                v.path = if v.path.is_empty() {
+                    target.clone()
+                } else {
+                    format!("{target}.{}", v.path)
+                };
+                v
+            }));
+        }
+        Ok(violations)
+    }
+
+    /// Compares the registered slash commands against the framework's commands without changing anything
+    ///
+    /// Commands are only added or updated if `register` is true and unknown commands are only removed if `delete` is true.<br>
+    /// The framework's own commands are removed where they are out of scope whenever `register` is true.<br>
+    /// If `guild_id` is `None` then the global commands are compared.
+    pub async fn diff_commands(
         &self,
         http: &Http,
-        cmd: &Command,
         guild_id: Option<GuildId>,
-    ) -> Result<()> {
-        match guild_id {
-            Some(g) => {
-                let commmand_cache = http
-                    .get_guild_application_commands(g.0)
-                    .await
-                    .unwrap()
+        register: bool,
+        delete: bool,
+    ) -> Result<RegistrationDiff> {
+        let registered = self.fetch_commands(http, guild_id).await?;
+
+        let mut local = Vec::new();
+        for cmd in self.commands.values() {
+            if self.registered_in(cmd, guild_id).await {
+                local.push(cmd);
+            }
+        }
+        local.sort_by_key(|c| c.name);
+        let local = local
+            .into_iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+
+        let mut protected = self.settings.protected_commands().await;
+        if register && !delete {
+            // Only the commands that left this scope are removed, unknown ones are kept
+            protected.extend(
+                registered
                     .iter()
-                    .map(|a| (a.name.clone(), a.id))
-                    .collect::<HashMap<String, CommandId>>();
-                if commmand_cache.contains_key(&cmd.name.to_owned()) {
-                    http.edit_guild_application_command(
-                        g.0,
-                        commmand_cache.get(&cmd.name.to_owned()).unwrap().0,
-                        &serde_json::to_value(cmd)?,
-                    )
-                    .await?;
-                } else {
-                    http.create_guild_application_command(g.0, &serde_json::to_value(cmd)?)
-                        .await?;
+                    .filter_map(|c| c.get("name").and_then(Value::as_str))
+                    .filter(|n| !self.commands.contains_key(n))
+                    .map(str::to_owned),
+            );
+        }
+        Ok(diff_commands(
+            &local,
+            &registered,
+            register,
+            register || delete,
+            &protected,
+        ))
+    }
+
+    /// Gets the changes auto-registration would make without making them
+    ///
+    /// Follows the [SettingsProvider]'s `auto_register`, `auto_delete` and `auto_register_guilds`, and the scope of each command, the same way as on Ready.<br>
+    /// Unlike Ready this doesn't know every guild the bot is in, so commands are only removed from guilds that left their scope since the framework started.
+    pub async fn registration_plan(&self, http: &Http) -> Result<RegistrationPlan> {
+        let register = self.settings.auto_register().await;
+        let delete = self.settings.auto_delete().await;
+
+        let global = if register || delete {
+            Some(self.diff_commands(http, None, register, delete).await?)
+        } else {
+            None
+        };
+
+        let mut guilds = Vec::new();
+        for guild_id in self.registration_guilds().await {
+            guilds.push((
+                guild_id,
+                self.diff_commands(http, Some(guild_id), true, delete)
+                    .await?,
+            ));
+        }
+
+        Ok(RegistrationPlan { global, guilds })
+    }
+
+    /// Makes the changes in a [RegistrationPlan]
+    ///
+    /// Each global or guild diff with changes is sent in one bulk overwrite.
+    pub async fn apply_registration_plan(
+        &self,
+        http: &Http,
+        plan: &RegistrationPlan,
+    ) -> Result<()> {
+        if let Some(diff) = &plan.global {
+            self.apply_diff(http, None, diff).await?;
+        }
+        for (guild_id, diff) in &plan.guilds {
+            self.apply_diff(http, Some(*guild_id), diff).await?;
+        }
+        Ok(())
+    }
+
+    /// Sends a diff in one bulk overwrite if it has any changes
+    async fn apply_diff(
+        &self,
+        http: &Http,
+        guild_id: Option<GuildId>,
+        diff: &RegistrationDiff,
+    ) -> Result<()> {
+        if diff.is_empty() {
+            return Ok(());
+        }
+
+        let body = Value::Array(diff.commands.clone());
+        let registered = match guild_id {
+            Some(g) => http.create_guild_application_commands(g.0, &body).await?,
+            None => http.create_global_application_commands(&body).await?,
+        };
+        // The bulk overwrite returns every command that is now registered
+        self.cache_commands(guild_id, registered.into_iter().map(|c| (c.name, c.id)))
+            .await;
+        Ok(())
+    }
+
+    /// Registers the commands for the global commands or a guild on Ready
+    ///
+    /// Requests are retried and any errors are reported instead of stopping registration
+    async fn register_on_ready(
+        &self,
+        http: &Http,
+        guild_id: Option<GuildId>,
+        register: bool,
+        delete: bool,
+    ) {
+        let retry_policy = self.registration_retry;
+        let diff = match retry(retry_policy, || {
+            self.diff_commands(http, guild_id, register, delete)
+        })
+        .await
+        {
+            Ok(diff) => diff,
+            Err(error) => {
+                self.report_registration_error(RegistrationError {
+                    guild_id,
+                    command: None,
+                    error,
+                });
+                return;
+            }
+        };
+
+        let result = retry(retry_policy, || self.apply_diff(http, guild_id, &diff)).await;
+        if let Err(error) = result {
+            self.report_registration_error(RegistrationError {
+                guild_id,
+                command: None,
+                error,
+            });
+
+            // The bulk overwrite fails if any command is invalid
+            // so register the new and changed commands and delete the removed ones one at a time to find the ones that failed
+            let names = diff.added.iter().chain(&diff.changed).collect::<Vec<_>>();
+            let commands = diff.commands.iter().filter(|c| {
+                c.get("name")
+                    .and_then(Value::as_str)
+                    .map(|n| names.iter().any(|name| *name == n))
+                    .unwrap_or(false)
+            });
+            for cmd in commands {
+                let result = retry(retry_policy, || async move {
+                    match guild_id {
+                        Some(g) => http.create_guild_application_command(g.0, cmd).await,
+                        None => http.create_global_application_command(cmd).await,
+                    }
+                })
+                .await;
+
+                match result {
+                    Ok(registered) =>
+                        self.cache_command(guild_id, registered.name, registered.id)
+                            .await,
+                    Err(error) => self.report_registration_error(RegistrationError {
+                        guild_id,
+                        command: cmd.get("name").and_then(Value::as_str).map(str::to_owned),
+                        error,
+                    }),
                 }
             }
+
+            for name in &diff.removed {
+                let result = retry(retry_policy, || {
+                    self.delete_slash_command(http, name, guild_id)
+                })
+                .await;
+                if let Err(error) = result {
+                    self.report_registration_error(RegistrationError {
+                        guild_id,
+                        command: Some(name.clone()),
+                        error,
+                    });
+                }
+            }
+        }
+    }
+
+    fn report_registration_error(&self, error: RegistrationError) {
+        match self.registration_error_handler {
+            Some(handler) => handler(&error),
+            None => eprintln!("{error}"),
+        }
+    }
+
+    /// Fetches the registered commands for the global commands or a guild and updates the command cache
+    async fn fetch_commands(&self, http: &Http, guild_id: Option<GuildId>) -> Result<Vec<Value>> {
+        let registered = match guild_id {
+            Some(g) => http.get_guild_application_commands(g.0).await?,
+            None => http.get_global_application_commands().await?,
+        };
+        self.cache_commands(guild_id, registered.iter().map(|c| (c.name.clone(), c.id)))
+            .await;
+
+        Ok(registered
+            .iter()
+            .map(serde_json::to_value)
+            .collect::<serde_json::Result<Vec<_>>>()?)
+    }
+
+    /// Replaces the cached commands for the global commands or a guild
+    async fn cache_commands(
+        &self,
+        guild_id: Option<GuildId>,
+        commands: impl Iterator<Item = (String, CommandId)>,
+    ) {
+        self.registered_command_cache
+            .write()
+            .await
+            .insert(guild_id, commands.collect());
+    }
+
+    /// Adds or updates a single cached command
+    async fn cache_command(&self, guild_id: Option<GuildId>, name: String, id: CommandId) {
+        self.registered_command_cache
+            .write()
+            .await
+            .entry(guild_id)
+            .or_default()
+            .insert(name, id);
+    }
+
+    /// Gets the id of a registered command, fetching the registered commands if they aren't cached yet
+    async fn cached_command_id(
+        &self,
+        http: &Http,
+        name: &str,
+        guild_id: Option<GuildId>,
+    ) -> Result<Option<CommandId>> {
+        let cached = self
+            .registered_command_cache
+            .read()
+            .await
+            .get(&guild_id)
+            .map(|commands| commands.get(name).copied());
+
+        match cached {
+            Some(id) => Ok(id),
             None => {
-                if self
+                self.fetch_commands(http, guild_id).await?;
+                Ok(self
                     .registered_command_cache
-                    .contains_key(&cmd.name.to_owned())
-                {
-                    http.edit_global_application_command(
-                        self.registered_command_cache
-                            .get(&cmd.name.to_owned())
-                            .unwrap()
-                            .0,
-                        &serde_json::to_value(cmd)?,
-                    )
-                    .await?;
-                } else {
-                    http.create_global_application_command(&serde_json::to_value(cmd)?)
-                        .await?;
-                }
+                    .read()
+                    .await
+                    .get(&guild_id)
+                    .and_then(|commands| commands.get(name).copied()))
             }
         }
+    }
+
+    /// Refetches the registered commands and replaces the cached ones
+    ///
+    /// Useful if commands were changed outside of the framework, eg. after a reconnect.<br>
+    /// If `guild_id` is `None` then the global commands are refetched.
+    pub async fn resync_command_cache(&self, http: &Http, guild_id: Option<GuildId>) -> Result<()> {
+        self.fetch_commands(http, guild_id).await?;
+        Ok(())
+    }
+
+    /// Register a Command as a slash command
+    ///
+    /// Edits the command if it is already registered.<br>
+    /// If `guild_id` is `None` then the command is registered globally
+    pub async fn register_slash_command(
+        &self,
+        http: &Http,
+        cmd: &Command,
+        guild_id: Option<GuildId>,
+    ) -> Result<()> {
+        let json = serde_json::to_value(cmd)?;
+        let id = self.cached_command_id(http, cmd.name, guild_id).await?;
+        let registered = match (guild_id, id) {
+            (Some(g), Some(id)) =>
+                http.edit_guild_application_command(g.0, id.0, &json)
+                    .await?,
+            (Some(g), None) => http.create_guild_application_command(g.0, &json).await?,
+            (None, Some(id)) => http.edit_global_application_command(id.0, &json).await?,
+            (None, None) => http.create_global_application_command(&json).await?,
+        };
+        self.cache_command(guild_id, registered.name, registered.id)
+            .await;
+        Ok(())
+    }
+
+    /// Delete a registered slash command
+    ///
+    /// Does nothing if the command isn't registered.<br>
+    /// If `guild_id` is `None` then the global command is deleted
+    pub async fn delete_slash_command(
+        &self,
+        http: &Http,
+        name: &str,
+        guild_id: Option<GuildId>,
+    ) -> Result<()> {
+        let id = match self.cached_command_id(http, name, guild_id).await? {
+            Some(id) => id,
+            None => return Ok(()),
+        };
+        match guild_id {
+            Some(g) => http.delete_guild_application_command(g.0, id.0).await?,
+            None => http.delete_global_application_command(id.0).await?,
+        }
+        if let Some(commands) = self
+            .registered_command_cache
+            .write()
+            .await
+            .get_mut(&guild_id)
+        {
+            commands.remove(name);
+        }
         Ok(())
     }
 }
@@ -174,7 +735,7 @@ macro_rules! event_handler_runners {
 
 #[async_trait]
 // #[cfg(not(test))]
-impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
+impl<T: SettingsProvider> EventHandler for Framework<T> {
     // Run any other EventHandlers we have registered
     event_handler_runners! {
         cache_ready, e, Vec<GuildId>;
@@ -227,33 +788,21 @@ impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
             handler.ready(ctx.clone(), data_about_bot.clone()).await
         }
 
-        // Allow unwraps here because we *should* panic if these fail
-
-        if self.settings.auto_delete() {
-            for (reg_name, reg_id) in &self.registered_command_cache {
-                if !self.commands.contains_key(reg_name.as_str()) {
-                    ctx.http
-                        .delete_global_application_command(reg_id.0)
-                        .await
-                        .unwrap();
-                }
-            }
+        let register = self.settings.auto_register().await;
+        let delete = self.settings.auto_delete().await;
+        if register || delete {
+            self.register_on_ready(&ctx.http, None, register, delete)
+                .await;
         }
 
-        if self.settings.auto_register() {
-            for cmd in self.commands.values() {
-                self.register_slash_command(&ctx.http, cmd, None)
-                    .await
-                    .unwrap()
-            }
-        }
-
-        for guild_id in self.settings.auto_register_guilds() {
-            for cmd in self.commands.values() {
-                self.register_slash_command(&ctx.http, cmd, Some(guild_id))
-                    .await
-                    .unwrap()
-            }
+        // Commands removed from a guild's scope before a restart are only found by checking every guild the bot is in
+        let mut guilds = self.registration_guilds().await;
+        guilds.extend(data_about_bot.guilds.iter().map(|g| g.id));
+        guilds.sort();
+        guilds.dedup();
+        for guild_id in guilds {
+            self.register_on_ready(&ctx.http, Some(guild_id), true, delete)
+                .await;
         }
     }
 
@@ -267,54 +816,109 @@ impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
             return;
         }
 
-        let mut found_prefix = String::new();
-
-        let prefix_list = match self
-            .settings
-            .prefixes(message.guild_id.unwrap_or(GuildId(0)))
-        {
+        let location = match message.guild_id {
+            Some(guild_id) => Location::Guild(guild_id, message.channel_id),
+            None => Location::Dm(message.channel_id),
+        };
+        let prefix_list = match self.settings.prefixes(location).await {
             Some(v) => v,
-            None => self.settings.default_prefixes(),
+            None => self.settings.default_prefixes().await,
+        };
+        let options = PrefixOptions {
+            mention: if self.settings.mention_prefix().await {
+                Some(ctx.cache.current_user_id())
+            } else {
+                None
+            },
+            whitespace: self.settings.prefix_whitespace().await,
+            case_insensitive: self.settings.case_insensitive_prefixes().await,
         };
 
-        for prefix in prefix_list {
-            if message.content.starts_with(&prefix) {
-                found_prefix = prefix;
-                break;
-            }
-        }
-
-        if found_prefix == String::new() {
-            return;
-        }
-
-        let cropped_msg = &message.content[found_prefix.len() ..].to_owned();
+        let prefix_len = match match_prefix(&message.content, &prefix_list, options) {
+            Some(len) => len,
+            None if matches!(location, Location::Dm(_)) && self.settings.no_dm_prefix().await => 0,
+            None => return,
+        };
+        // The prefix as it was typed so it can be cropped off again later
+        let found_prefix = message.content[.. prefix_len].to_owned();
+        let cropped_msg = &message.content[prefix_len ..].to_owned();
 
+        let case_insensitive = self.settings.case_insensitive().await;
         let cmd_str = cropped_msg.split(' ').next().unwrap_or_default();
+        let cmd_str = if case_insensitive {
+            cmd_str.to_lowercase()
+        } else {
+            cmd_str.to_owned()
+        };
+
+        if let Some(cmd) = self.commands.get(cmd_str.as_str()) {
+            let parsed = Argument::parse_message_with_case(
+                cropped_msg,
+                &cmd.arguments_tree,
+                case_insensitive,
+            );
 
-        if let Some(cmd) = self.commands.get(cmd_str) {
             #[cfg(debug_assertions)]
             let source = CommandSource::Message(message.clone());
             #[cfg(not(debug_assertions))]
             // Don't clone message if we aren't using it later
             let source = CommandSource::Message(message);
 
-            if let Some((args, func)) = Argument::parse(&source, &cmd.arguments_tree) {
+            if let Some((args, func)) = parsed {
                 #[cfg(debug_assertions)]
-                let context = CommandContext::new(ctx.clone(), source, args);
+                let context = CommandContext::new(
+                    ctx.clone(),
+                    source,
+                    args,
+                    self.commands.clone(),
+                    found_prefix,
+                    self.settings.clone(),
+                );
                 #[cfg(not(debug_assertions))]
                 // Don't clone ctx if we don't need to
-                let context = CommandContext::new(ctx, source, args);
-                if let Err(e) = func(&context).await {
+                let context = CommandContext::new(
+                    ctx,
+                    source,
+                    args,
+                    self.commands.clone(),
+                    found_prefix,
+                    self.settings.clone(),
+                );
+                if let Err(e) = self.run_command(cmd, func, &context).await {
+                    match self.error_handler {
+                        Some(handler) => handler(&context, e).await,
+                        None => {
+                            eprintln!("{e:?}");
+                            #[cfg(debug_assertions)]
+                            // message sends should only fail on perm errors or too many chars
+                            // neither *should* occur while testing
+                            message
+                                .channel_id
+                                .send_message(ctx, |m| m.content(format!("Error: {e}")))
+                                .await
+                                .unwrap();
+                        }
+                    }
+                }
+            } else {
+                // The first word is always the command name
+                let words = Argument::get_arg_strings(cropped_msg);
+                let usage = cmd.usage_for(
+                    &found_prefix,
+                    words.get(1 ..).unwrap_or_default(),
+                    case_insensitive,
+                );
+                let sent = message
+                    .channel_id
+                    .send_message(ctx, |m| {
+                        m.content(format!(
+                            "Invalid arguments for command {}\nUsage: `{usage}`",
+                            cmd.name
+                        ))
+                    })
+                    .await;
+                if let Err(e) = sent {
                     eprintln!("{e:?}");
-                    #[cfg(debug_assertions)]
-                    // message sends should only fail on perm errors or too many chars
-                    // neither *should* occur while testing
-                    message
-                        .channel_id
-                        .send_message(ctx, |m| m.content(format!("Error: {e}")))
-                        .await
-                        .unwrap();
                 }
             }
         }
@@ -344,21 +948,38 @@ impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
                 match Argument::parse(&source, &cmd.arguments_tree) {
                     Some((args, func)) => {
                         #[cfg(debug_assertions)]
-                        let context = CommandContext::new(ctx.clone(), source, args);
+                        let context = CommandContext::new(
+                            ctx.clone(),
+                            source,
+                            args,
+                            self.commands.clone(),
+                            "/".to_owned(),
+                            self.settings.clone(),
+                        );
                         #[cfg(not(debug_assertions))]
                         // Don't clone ctx if we don't need to
-                        let context = CommandContext::new(ctx, source, args);
-                        match func(&context).await {
+                        let context = CommandContext::new(
+                            ctx,
+                            source,
+                            args,
+                            self.commands.clone(),
+                            "/".to_owned(),
+                            self.settings.clone(),
+                        );
+                        match self.run_command(cmd, func, &context).await {
                             Ok(_) => {}
-                            Err(e) => {
-                                eprintln!("{e:?}");
-                                #[cfg(debug_assertions)]
-                                app_cmd
-                                    .channel_id
-                                    .send_message(ctx, |m| m.content(e))
-                                    .await
-                                    .unwrap();
-                            }
+                            Err(e) => match self.error_handler {
+                                Some(handler) => handler(&context, e).await,
+                                None => {
+                                    eprintln!("{e:?}");
+                                    #[cfg(debug_assertions)]
+                                    app_cmd
+                                        .channel_id
+                                        .send_message(ctx, |m| m.content(e))
+                                        .await
+                                        .unwrap();
+                                }
+                            },
                         }
                     }
                     // Do nothing rn
@@ -367,7 +988,15 @@ impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
                         app_cmd
                             .channel_id
                             .send_message(ctx, |m| {
-                                m.content(format!("Invalid arguments for command {}", cmd.name))
+                                m.content(format!(
+                                    "Invalid arguments for command {}\nUsage: `{}`",
+                                    cmd.name,
+                                    cmd.usage_for(
+                                        "/",
+                                        &Argument::interaction_path(&app_cmd),
+                                        false
+                                    )
+                                ))
                             })
                             .await
                             .unwrap();
@@ -383,12 +1012,120 @@ impl<T: SettingsProvider + Send + Sync> EventHandler for Framework<T> {
     }
 }
 
+/// How prefixes are matched against the start of a message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PrefixOptions {
+    /// The bot's id if mentioning the bot can be used as a prefix
+    pub mention: Option<UserId>,
+    /// Whether there can be whitespace between the prefix and the command
+    pub whitespace: bool,
+    /// Whether prefixes are matched ignoring case
+    pub case_insensitive: bool,
+}
+
+/// Finds the prefix a message starts with
+///
+/// Returns the length of the prefix, including any whitespace after it that should be skipped.<br>
+/// If more than one prefix matches the longest one wins.
+/// ```
+/// # use slashy::framework::{match_prefix, PrefixOptions};
+/// # use slashy::serenity::model::id::UserId;
+/// let prefixes = vec!["!".to_owned(), "!!".to_owned()];
+/// let options = PrefixOptions {
+///     mention: Some(UserId(42)),
+///     whitespace: true,
+///     case_insensitive: false,
+/// };
+///
+/// assert_eq!(match_prefix("!!stats", &prefixes, options), Some(2));
+/// assert_eq!(match_prefix("! stats", &prefixes, options), Some(2));
+/// assert_eq!(match_prefix("<@!42> stats", &prefixes, options), Some(7));
+/// assert_eq!(match_prefix("stats", &prefixes, options), None);
+/// ```
+pub fn match_prefix(content: &str, prefixes: &[String], options: PrefixOptions) -> Option<usize> {
+    let mentions = match options.mention {
+        Some(id) => vec![format!("<@{}>", id.0), format!("<@!{}>", id.0)],
+        None => Vec::new(),
+    };
+
+    // Returns the length of the matched prefix in the message, ignoring case this can differ from the prefix's length
+    let matched_len = |prefix: &str| {
+        if !options.case_insensitive {
+            return content.starts_with(prefix).then_some(prefix.len());
+        }
+        let mut chars = content.char_indices();
+        for p in prefix.chars() {
+            let (_, c) = chars.next()?;
+            if !c.to_lowercase().eq(p.to_lowercase()) {
+                return None;
+            }
+        }
+        Some(chars.next().map_or(content.len(), |(i, _)| i))
+    };
+
+    let found = prefixes
+        .iter()
+        // An empty prefix would match every message
+        .filter(|p| !p.is_empty())
+        .filter_map(|p| matched_len(p))
+        .map(|len| (len, options.whitespace))
+        // Discord puts a space after mentions so whitespace is always allowed after one
+        .chain(
+            mentions
+                .iter()
+                .filter(|m| content.starts_with(m.as_str()))
+                .map(|m| (m.len(), true)),
+        )
+        .max_by_key(|(len, _)| *len)?;
+
+    match found {
+        (len, true) => Some(content.len() - content[len ..].trim_start().len()),
+        (len, false) => Some(len),
+    }
+}
+
 /// A trait impl-ed automatically by the command macro to init commands with the framework
 pub trait CommandInit {
     /// The function run to initialize the command
     fn command_init() -> Command;
 }
 
+/// A trait to init a [CommandGroup] and all its commands with the framework
+///
+/// ```
+/// # use slashy::commands::*;
+/// # use slashy::framework::*;
+/// # use slashy::permissions::ADMINISTRATOR;
+/// # use slashy::{command, subcommand};
+/// # #[subcommand]
+/// # async fn ban(_ctx: &CommandContext) -> CommandResult {Ok(())}
+/// command! {
+///     ban,
+///     "bans a user",
+///     ban,
+///     [
+///         required User user | "the user to ban"
+///     ]
+/// }
+///
+/// pub struct MODERATION_GROUP;
+/// impl GroupInit for MODERATION_GROUP {
+///     fn group_init() -> CommandGroup {
+///         CommandGroup {
+///             name: "moderation",
+///             description: "commands for moderators",
+///             checks: vec![ADMINISTRATOR],
+///             works_in_dms: false,
+///             commands: vec![BAN_COMMAND::command_init()],
+///         }
+///     }
+/// }
+/// ```
+pub trait GroupInit {
+    /// The function run to initialize the group
+    fn group_init() -> CommandGroup;
+}
+
 /// Stores the source the command was called from
 #[allow(missing_docs)]
 pub enum CommandSource {
@@ -403,6 +1140,9 @@ pub struct CommandContext {
     pub ctx: Context,
     source: CommandSource,
     args: HashMap<String, Argument>,
+    commands: Arc<HashMap<&'static str, Command>>,
+    prefix: String,
+    settings: Arc<dyn SettingsProvider>,
 }
 // TODO: Figure out why this can't be #[cfg(test)]
 impl CommandContext {
@@ -431,6 +1171,9 @@ impl CommandContext {
                 std::mem::MaybeUninit::zeroed().assume_init()
             }),
             args,
+            commands: Arc::new(HashMap::new()),
+            prefix: String::new(),
+            settings: Arc::new(Settings::default()),
         }
     }
 }
@@ -464,8 +1207,18 @@ impl CommandContext {
         ctx: Context,
         source: CommandSource,
         args: HashMap<String, Argument>,
+        commands: Arc<HashMap<&'static str, Command>>,
+        prefix: String,
+        settings: Arc<dyn SettingsProvider>,
     ) -> Self {
-        CommandContext { ctx, args, source }
+        CommandContext {
+            ctx,
+            args,
+            source,
+            commands,
+            prefix,
+            settings,
+        }
     }
 
     /// Gets an argument
@@ -473,6 +1226,78 @@ impl CommandContext {
         self.args.get(key)
     }
 
+    /// Gets the variant of a [SlashyChoice] enum an argument was set to
+    ///
+    /// `None` if the argument wasn't given or isn't one of the enum's choices
+    pub fn get_choice_arg<C: SlashyChoice>(&self, key: &str) -> Option<C> {
+        C::from_argument(self.get_arg(key)?)
+    }
+
+    /// Reads the arguments into a struct deriving [SlashyArgs](trait@SlashyArgs)
+    ///
+    /// Errors if a required argument is missing or an argument has an invalid value
+    pub fn parse_args<A: SlashyArgs>(&self) -> CommandResult<A> {
+        Ok(A::from_args(&self.args)?)
+    }
+
+    /// Gets all the commands registered with the framework
+    pub fn commands(&self) -> &HashMap<&'static str, Command> {
+        &self.commands
+    }
+
+    /// Gets the settings provider of the framework
+    pub fn settings(&self) -> &dyn SettingsProvider {
+        self.settings.as_ref()
+    }
+
+    /// Gets where the command was used
+    pub fn location(&self) -> Location {
+        match self.guild_id() {
+            Some(guild_id) => Location::Guild(guild_id, self.channel_id()),
+            None => Location::Dm(self.channel_id()),
+        }
+    }
+
+    /// Gets the prefix the command was triggered with
+    ///
+    /// This is always `/` for slash commands
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Gets the words given after the command name
+    ///
+    /// For slash commands these are only the names of the subcommands as arguments are sent seperately
+    pub(crate) fn subcommand_words(&self) -> Vec<&str> {
+        match &self.source {
+            CommandSource::Interaction(i) => Argument::interaction_path(i),
+            CommandSource::Message(m) => {
+                let mut words = Argument::get_arg_strings(&m.content[self.prefix.len() ..]);
+                // The first word is the command's name
+                if !words.is_empty() {
+                    words.remove(0);
+                }
+                words
+            }
+        }
+    }
+
+    /// Gets the id of the User that triggered the command
+    pub fn author_id(&self) -> UserId {
+        match &self.source {
+            CommandSource::Interaction(i) => i.user.id,
+            CommandSource::Message(m) => m.author.id,
+        }
+    }
+
+    /// Gets the id of the channel the command was triggered in
+    pub fn channel_id(&self) -> ChannelId {
+        match &self.source {
+            CommandSource::Interaction(i) => i.channel_id,
+            CommandSource::Message(m) => m.channel_id,
+        }
+    }
+
     /// Gets the User that triggered the command
     pub fn author(&self) -> Option<User> {
         match &self.source {
@@ -607,3 +1432,25 @@ impl Debug for CommandContext {
         Ok(())
     }
 }
+
+#[test]
+fn match_prefix_test() {
+    let prefixes = vec!["s!".to_owned(), String::new()];
+    let options = PrefixOptions::default();
+
+    assert_eq!(match_prefix("s!stats", &prefixes, options), Some(2));
+    // Whitespace isn't skipped unless allowed
+    assert_eq!(match_prefix("s! stats", &prefixes, options), Some(2));
+    assert_eq!(match_prefix("S!stats", &prefixes, options), None);
+    // Mentions aren't prefixes unless enabled
+    assert_eq!(match_prefix("<@42> stats", &prefixes, options), None);
+
+    let options = PrefixOptions {
+        case_insensitive: true,
+        ..options
+    };
+    assert_eq!(match_prefix("S!stats", &prefixes, options), Some(2));
+    // Case pairs can have a different length in bytes
+    let prefixes = vec!["ß!".to_owned()];
+    assert_eq!(match_prefix("ẞ!stats", &prefixes, options), Some(4));
+}
@@ -0,0 +1,131 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    command,
+    commands::{Command, CommandArguments, CommandResult, SlashyError},
+    framework::CommandContext,
+    subcommand,
+};
+
+command! {
+    help,
+    "lists all commands or shows how to use one",
+    help,
+    [
+        optional String command | "the command to get help for"
+    ]
+}
+
+/// The category commands without one are listed under
+const UNCATEGORIZED: &str = "Uncategorized";
+
+#[subcommand]
+async fn help(ctx: &CommandContext) -> CommandResult {
+    match ctx.get_str_arg("command") {
+        Some(name) => {
+            let cmd = ctx
+                .commands()
+                .get(name.to_lowercase().as_str())
+                .ok_or_else(|| SlashyError::new(&format!("No command named `{name}`")))?;
+            let description = command_help(cmd, ctx.prefix());
+
+            ctx.send_embed(|e| e.title(cmd.name).description(&description))
+                .await?;
+        }
+        None => {
+            let description = command_list(ctx);
+
+            ctx.send_embed(|e| e.title("Commands").description(&description))
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lists every command with its description grouped by category
+fn command_list(ctx: &CommandContext) -> String {
+    let mut categories = BTreeMap::new();
+    for cmd in ctx.commands().values() {
+        categories
+            .entry(cmd.category.unwrap_or(UNCATEGORIZED))
+            .or_insert_with(Vec::new)
+            .push(cmd);
+    }
+
+    let mut output = String::new();
+    for (category, mut cmds) in categories {
+        cmds.sort_by_key(|c| c.name);
+
+        output.push_str(&format!("**{category}**\n"));
+        for cmd in cmds {
+            output.push_str(&format!(
+                "`{}{}` - {}\n",
+                ctx.prefix(),
+                cmd.name,
+                cmd.description
+            ));
+        }
+        output.push('\n');
+    }
+
+    output.push_str(&format!(
+        "Use `{}help <command>` to see how to use a command",
+        ctx.prefix()
+    ));
+
+    output
+}
+
+/// Shows the usage of a command and describes all of its arguments
+fn command_help(cmd: &Command, prefix: &str) -> String {
+    let mut output = format!("{}\n\n**Usage**\n", cmd.description);
+    for line in cmd.usage_lines(prefix) {
+        output.push_str(&format!("`{line}`\n"));
+    }
+
+    if let Some(children) = &cmd.arguments_tree.children {
+        output.push_str("\n**Arguments**\n");
+        describe_arguments(children, &mut output);
+    }
+
+    output
+}
+
+fn describe_arguments(args: &[CommandArguments], output: &mut String) {
+    for arg in args {
+        output.push_str(&format!("`{}` - {}", arg.name(), arg.description()));
+
+        let choices = match arg {
+            CommandArguments::String {
+                choices: Some(c), ..
+            } => c
+                .iter()
+                .map(|c| format!("{}: {}", c.name, c.value))
+                .collect(),
+            CommandArguments::Integer {
+                choices: Some(c), ..
+            } => c
+                .iter()
+                .map(|c| format!("{}: {}", c.name, c.value))
+                .collect(),
+            _ => Vec::new(),
+        };
+        if !choices.is_empty() {
+            output.push_str(&format!(" ({})", choices.join(", ")));
+        }
+        output.push('\n');
+
+        if let CommandArguments::SubCommand {
+            options: Some(children),
+            ..
+        }
+        | CommandArguments::SubCommandGroup {
+            options: Some(children),
+            ..
+        } = arg
+        {
+            describe_arguments(children, output);
+        }
+    }
+}
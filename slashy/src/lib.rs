@@ -2,17 +2,40 @@
 
 //! A command framework for Serenity that allows commands to be registered both as a traditional text command and a discord slash command
 
+// Lets the built in commands use the same macros as everyone else
+extern crate self as slashy;
+
 pub use serenity;
+/// Built in commands for server admins to enable and disable commands.
+///
+/// The commands are opt-in, add them with `.group::<ADMIN_GROUP>()`
+pub mod admin;
 /// Everything related to parsing and representing arguments.
 pub mod argument;
 /// Everything related to commands.
 pub mod commands;
+/// Limits on how many uses of a command can run at once.
+pub mod concurrency;
+/// Cooldowns that limit how often commands can be used.
+pub mod cooldown;
 /// Everything related to the framework metadata and handlers.
 pub mod framework;
+/// A built in help command generated from the registered commands.
+///
+/// The command is opt-in, add it like any other command with `.command::<HELP_COMMAND>()`
+pub mod help;
 /// Default permission checks and PermissionCheck type
 pub mod permissions;
+/// A built in command for changing the prefixes of a server.
+///
+/// The command is opt-in, add it with `.group::<PREFIX_GROUP>()`
+pub mod prefix;
+/// Comparing the framework's commands against the registered slash commands.
+pub mod registration;
 /// The settings for the framework.
 pub mod settings;
+/// Checking commands against discord's rules for slash commands without registering them.
+pub mod validation;
 
 /// Macro to create commands.
 ///
@@ -23,11 +46,53 @@ pub mod settings;
 ///     description,
 ///     function,
 ///     [
-///         required Type name = function | "description" {choices: map} [children]
+///         required Type name = function | "description" aliases("alias") {choices: map} [children]
+///     ],
+///     modifiers
+/// }
+/// ```
+///
+/// ## Descriptions
+/// The description of the command or an argument can be given as a doc comment instead of a string.<br>
+/// If a command has neither, the doc comment of its function is used.
+/// When the function is imported from another module, import it with a glob or also import the hidden `__SLASHY_DESC_<NAME>` constant `#[subcommand]` stores the doc comment in.
+/// ```
+/// # use slashy_macros::*;
+/// # use slashy::commands::*;
+/// # use slashy::framework::*;
+/// /// ping pong
+/// #[subcommand]
+/// async fn pong(_ctx: &CommandContext) -> CommandResult {Ok(())}
+///
+/// command!{
+///     ping,
+///     pong,
+///     [
+///         /// text to echo
+///         optional String text
 ///     ]
 /// }
 /// ```
 ///
+/// ## Modifiers
+/// Modifiers are optional extra settings for the command given after the arguments.
+/// - `category("name")` sets the category the command is listed under in the help command
+/// - `group("name")` puts the command in the [CommandGroup](crate::commands::CommandGroup) with that name,
+///   the group has to be added to the framework before the command and is also used as the category if none is set
+/// - `cooldown(uses, seconds, Bucket)` limits the command to `uses` uses every `seconds` seconds for each [Bucket](crate::cooldown::BucketType)
+/// - `max_concurrency(limit, Bucket, Mode)` limits the command to `limit` uses running at once for each [Bucket](crate::cooldown::BucketType),
+///   either rejecting or queueing uses past the limit depending on the [Mode](crate::concurrency::ConcurrencyMode)
+/// - `scope(Scope)` sets where the command is registered and can be used, one of `Global`, `Guilds(id, id)` or `Dynamic`,
+///   see [CommandScope](crate::commands::CommandScope)
+/// - `args(Type)` uses the fields of a struct deriving [SlashyArgs](derive@SlashyArgs) as the arguments instead of a list
+/// - `struct(pub(crate) Name)` sets the visibility and name of the generated struct, by default it's `pub struct {NAME}_COMMAND`
+///
+/// The generated code only uses fully qualified paths so any number of commands can be defined in the same module,
+/// [CommandInit](crate::framework::CommandInit) has to be in scope to call `command_init` on the generated struct.
+///
+/// SubCommands and SubCommandGroups can also be given a `cooldown` after their description.<br>
+/// The cooldowns of a command and every subcommand that was used all have to be available for it to run.
+///
 /// ## Argument Types
 /// - Integer (u32)
 /// - Str (String)
@@ -39,8 +104,8 @@ pub mod settings;
 /// - SubCommandGroup
 ///
 /// ### Type Specific Fields
-/// The fields `children`, `choices`, and `function` are all only valid for some of the argument types.<br>
-/// Only SubCommand and SubCommandGroup arguments can have `function` or `children`.<br>
+/// The fields `children`, `choices`, `aliases`, and `function` are all only valid for some of the argument types.<br>
+/// Only SubCommand and SubCommandGroup arguments can have `function`, `aliases` or `children`.<br>
 /// And only Integer and Str can have `choices`.
 ///
 /// ## SubCommands
@@ -50,6 +115,9 @@ pub mod settings;
 ///
 /// SubCommands can also have child arguments and their functions will only be run when all required arguments are present.
 ///
+/// SubCommands and SubCommandGroups can be given aliases which are accepted in text commands.<br>
+/// Slash commands only ever use the real name.
+///
 /// ## Examples
 /// `stats get|set [user]`
 /// ```
@@ -115,7 +183,8 @@ pub mod settings;
 /// ```
 ///
 /// You have to follow all the rules of normal discord slash commands.<br>
-/// This includes not allowing required arguments after optional ones.
+/// They are checked when compiling, this includes lowercase names, description and choice lengths, the number of arguments and choices,
+/// how SubCommands can be nested and not allowing required arguments after optional ones.
 ///
 /// ```compile_fail
 /// # use slashy_macros::*;
@@ -143,3 +212,65 @@ pub use slashy_macros::subcommand;
 
 /// Denotes a function that is used to check permissions before running a command.
 pub use slashy_macros::permissions_check;
+
+/// The attribute form of [command](macro@command).
+pub mod attr {
+    /// Creates a command from an async function.
+    ///
+    /// The command is named after the function and its description is the function's doc comment.<br>
+    /// Each parameter after the [CommandContext](crate::framework::CommandContext) is an argument,
+    /// read from the context before the function runs, the same way as the fields of [SlashyArgs](derive@crate::SlashyArgs).<br>
+    /// Their descriptions are their doc comments or given with `#[arg(description = "...")]`,
+    /// which also takes the same `choices`, `min` and `max` settings as [SlashyArgs](derive@crate::SlashyArgs).
+    ///
+    /// The attribute takes the same modifiers as [command](macro@crate::command), apart from `args`.
+    /// ```
+    /// # use slashy::{attr::command, commands::CommandResult, framework::CommandContext};
+    /// # use slashy::serenity::model::id::UserId;
+    /// /// bans a user
+    /// #[command(category("moderation"))]
+    /// async fn ban(
+    ///     ctx: &CommandContext,
+    ///     /// the user to ban
+    ///     user: UserId,
+    ///     #[arg(description = "why they were banned")] reason: Option<String>,
+    /// ) -> CommandResult {
+    ///     ctx.send_str(&format!("Banned {user} for {reason:?}")).await?;
+    ///     Ok(())
+    /// }
+    /// // Add it with .command::<BAN_COMMAND>()
+    /// ```
+    pub use slashy_macros::command_attribute as command;
+}
+
+/// Derives [SlashyArgs](trait@crate::argument::SlashyArgs) for a struct with named fields.
+///
+/// Each field is an argument named after the field, its type has to implement [FromArgument](crate::argument::FromArgument).<br>
+/// Fields with an `Option` type are optional arguments and, like in [command], have to come after the required ones.
+///
+/// The description of each field is given with `#[arg(description = "...")]`, or taken from its doc comment.
+///
+/// `#[arg(...)]` also takes
+/// - `choices = {"name": value}` to give a String or integer field choices, using the same format as [command]
+/// - `min = 1` and `max = 10` to limit the value of an integer field, they are sent to discord and values outside of them are rejected when the arguments are read
+/// ```
+/// # use slashy::SlashyArgs;
+/// #[derive(SlashyArgs)]
+/// struct GridArgs {
+///     #[arg(description = "the size of the grid", choices = {"small": 1, "medium": 5})]
+///     size: i64,
+///     #[arg(description = "the gap between cells", min = 0, max = 4)]
+///     gap: Option<i64>,
+/// }
+/// ```
+pub use slashy_macros::SlashyArgs;
+
+/// Derives [SlashyChoice](trait@crate::argument::SlashyChoice) and [FromArgument](crate::argument::FromArgument) for a fieldless enum.
+///
+/// Each variant is a choice of a String argument.<br>
+/// The name shown in discord defaults to the variant's name and the value to the variant's name in snake case,
+/// either can be changed with `#[choice(name = "...", value = "...")]`.
+///
+/// If the variants have integer values, given as discriminants like `Small = 1` or with `#[choice(value = 1)]`,
+/// they are the choices of an Integer argument instead. Every variant needs a value of the same type.
+pub use slashy_macros::SlashyChoice;
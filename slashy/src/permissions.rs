@@ -1,7 +1,6 @@
 use serenity::{
     client::Context,
     futures::future::BoxFuture,
-    http::Http,
     model::{channel::GuildChannel, guild::Member},
 };
 
@@ -11,7 +10,7 @@ use slashy_macros::permissions_check;
 
 /// A permissions check
 pub type PermissionsCheck =
-    for<'a> fn(&'a Http, &'a Member, &'a GuildChannel) -> BoxFuture<'a, PermsResult>;
+    for<'a> fn(&'a Context, &'a Member, &'a GuildChannel) -> BoxFuture<'a, PermsResult>;
 /// The return type of a permissions check
 pub type PermsResult = CommandResult<bool>;
 
@@ -40,3 +39,14 @@ pub async fn MANNAGE_MESSAGES(
             .permissions_for_user(ctx, member.user.id)?
             .manage_messages())
 }
+
+/// Permission check that passes if the member has the manage guild permission
+#[allow(non_snake_case)]
+#[permissions_check]
+pub async fn MANAGE_GUILD(
+    ctx: &Context,
+    member: &Member,
+    _channel: &GuildChannel,
+) -> CommandResult<bool> {
+    Ok(member.permissions(ctx)?.manage_guild())
+}
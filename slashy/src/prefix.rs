@@ -0,0 +1,131 @@
+use serenity::model::id::GuildId;
+
+use crate::{
+    command,
+    commands::{CommandGroup, CommandResult, SlashyError},
+    framework::{CommandContext, CommandInit, GroupInit},
+    permissions::MANAGE_GUILD,
+    settings::Location,
+    subcommand,
+};
+
+command! {
+    prefix,
+    "changes the prefixes used in this server",
+    [
+        optional SubCommand list = list | "lists the prefixes used in this server",
+        optional SubCommand set = set | "replaces all prefixes with one prefix" [
+            required String prefix | "the new prefix"
+        ],
+        optional SubCommand add = add | "adds a prefix" [
+            required String prefix | "the prefix to add"
+        ],
+        optional SubCommand remove = remove | "removes a prefix" [
+            required String prefix | "the prefix to remove"
+        ]
+    ]
+}
+
+/// The group containing the built in prefix command
+///
+/// Only members with the manage guild permission can use it.<br>
+/// Add it with `.group::<PREFIX_GROUP>()`, the prefixes are stored through the [SettingsProvider](crate::settings::SettingsProvider)
+#[allow(non_camel_case_types)]
+pub struct PREFIX_GROUP;
+impl GroupInit for PREFIX_GROUP {
+    fn group_init() -> CommandGroup {
+        CommandGroup {
+            name: "prefix",
+            description: "commands to change the bot's prefixes",
+            checks: vec![MANAGE_GUILD],
+            works_in_dms: false,
+            commands: vec![PREFIX_COMMAND::command_init()],
+        }
+    }
+}
+
+#[subcommand]
+async fn list(ctx: &CommandContext) -> CommandResult {
+    let prefixes = current_prefixes(ctx, guild(ctx)?).await;
+    let list = prefixes
+        .iter()
+        .map(|p| format!("`{p}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    ctx.send_str(&format!("Prefixes in this server: {list}"))
+        .await?;
+    Ok(())
+}
+
+#[subcommand]
+async fn set(ctx: &CommandContext) -> CommandResult {
+    let guild_id = guild(ctx)?;
+    let prefix = prefix_arg(ctx)?;
+
+    ctx.settings()
+        .set_prefixes(guild_id, vec![prefix.to_owned()])
+        .await?;
+    ctx.send_str(&format!("Set the prefix to `{prefix}`"))
+        .await?;
+    Ok(())
+}
+
+#[subcommand]
+async fn add(ctx: &CommandContext) -> CommandResult {
+    let guild_id = guild(ctx)?;
+    let prefix = prefix_arg(ctx)?;
+
+    let mut prefixes = current_prefixes(ctx, guild_id).await;
+    if prefixes.iter().any(|p| p == prefix) {
+        return Err(SlashyError::new(&format!("`{prefix}` is already a prefix")).into());
+    }
+    prefixes.push(prefix.to_owned());
+
+    ctx.settings().set_prefixes(guild_id, prefixes).await?;
+    ctx.send_str(&format!("Added the prefix `{prefix}`"))
+        .await?;
+    Ok(())
+}
+
+#[subcommand]
+async fn remove(ctx: &CommandContext) -> CommandResult {
+    let guild_id = guild(ctx)?;
+    let prefix = prefix_arg(ctx)?;
+
+    let mut prefixes = current_prefixes(ctx, guild_id).await;
+    if !prefixes.iter().any(|p| p == prefix) {
+        return Err(SlashyError::new(&format!("`{prefix}` is not a prefix")).into());
+    }
+    if prefixes.len() == 1 {
+        return Err(SlashyError::new("A server needs at least one prefix").into());
+    }
+    prefixes.retain(|p| p != prefix);
+
+    ctx.settings().set_prefixes(guild_id, prefixes).await?;
+    ctx.send_str(&format!("Removed the prefix `{prefix}`"))
+        .await?;
+    Ok(())
+}
+
+fn guild(ctx: &CommandContext) -> CommandResult<GuildId> {
+    Ok(ctx
+        .guild_id()
+        .ok_or_else(|| SlashyError::new("Prefixes can only be changed in servers"))?)
+}
+
+fn prefix_arg(ctx: &CommandContext) -> CommandResult<&str> {
+    match ctx.get_str_arg("prefix").map(|p| p.trim()) {
+        Some(p) if !p.is_empty() => Ok(p),
+        _ => Err(Box::new(SlashyError::new("A prefix can't be empty"))),
+    }
+}
+
+/// Gets the prefixes of the guild, falling back to the defaults like the framework does
+async fn current_prefixes(ctx: &CommandContext, guild_id: GuildId) -> Vec<String> {
+    let location = Location::Guild(guild_id, ctx.channel_id());
+    match ctx.settings().prefixes(location).await {
+        Some(prefixes) => prefixes,
+        None => ctx.settings().default_prefixes().await,
+    }
+}
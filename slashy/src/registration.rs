@@ -0,0 +1,467 @@
+use std::{error::Error, fmt::Display, future::Future, time::Duration};
+
+use serde_json::{Map, Value};
+use serenity::{http::HttpError, model::id::GuildId};
+
+/// The changes needed to make the registered slash commands match the framework's commands
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RegistrationDiff {
+    /// Commands that aren't registered yet
+    pub added: Vec<String>,
+    /// Commands that are registered but differ from the framework's
+    pub changed: Vec<String>,
+    /// Registered commands that the framework doesn't have
+    pub removed: Vec<String>,
+    /// What changed in each added, changed or removed command
+    pub changes: Vec<CommandChange>,
+    /// Every command that should be registered once the changes are made
+    ///
+    /// This is the body sent to the bulk overwrite endpoint
+    pub commands: Vec<Value>,
+}
+
+impl RegistrationDiff {
+    /// Whether the registered commands already match
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// A change to a single command
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandChange {
+    /// The name of the command
+    pub name: String,
+    /// The registered command, `None` if it is being added
+    pub before: Option<Value>,
+    /// The command once it is registered, `None` if it is being removed
+    pub after: Option<Value>,
+}
+
+impl CommandChange {
+    /// Lists every field that differs between the registered command and the new one
+    ///
+    /// Each line is the path to the field followed by the old and new value, eg. `options[0].required: false -> true`
+    pub fn differences(&self) -> Vec<String> {
+        let mut output = Vec::new();
+        json_diff(
+            &self.name,
+            self.before.as_ref().unwrap_or(&Value::Null),
+            self.after.as_ref().unwrap_or(&Value::Null),
+            &mut output,
+        );
+        output
+    }
+}
+
+fn json_diff(path: &str, before: &Value, after: &Value, output: &mut Vec<String>) {
+    match (before, after) {
+        (Value::Object(b), Value::Object(a)) =>
+            for key in b.keys().chain(a.keys().filter(|k| !b.contains_key(*k))) {
+                json_diff(
+                    &format!("{path}.{key}"),
+                    b.get(key).unwrap_or(&Value::Null),
+                    a.get(key).unwrap_or(&Value::Null),
+                    output,
+                );
+            },
+        (Value::Array(b), Value::Array(a)) =>
+            for i in 0 .. b.len().max(a.len()) {
+                json_diff(
+                    &format!("{path}[{i}]"),
+                    b.get(i).unwrap_or(&Value::Null),
+                    a.get(i).unwrap_or(&Value::Null),
+                    output,
+                );
+            },
+        _ if before != after => output.push(format!("{path}: {before} -> {after}")),
+        _ => {}
+    }
+}
+
+/// The changes registration would make to the global commands and the commands of each guild
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RegistrationPlan {
+    /// The changes to the global commands, `None` if they aren't being registered or deleted
+    pub global: Option<RegistrationDiff>,
+    /// The changes to the commands of each guild
+    pub guilds: Vec<(GuildId, RegistrationDiff)>,
+}
+
+impl RegistrationPlan {
+    /// Whether registration wouldn't change anything
+    pub fn is_empty(&self) -> bool {
+        self.global.iter().all(RegistrationDiff::is_empty)
+            && self.guilds.iter().all(|(_, d)| d.is_empty())
+    }
+}
+
+impl Display for RegistrationPlan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let diffs = self.global.iter().map(|d| ("global".to_owned(), d)).chain(
+            self.guilds
+                .iter()
+                .map(|(g, d)| (format!("guild {}", g.0), d)),
+        );
+
+        for (target, diff) in diffs {
+            writeln!(f, "{target}: {diff}")?;
+            for change in &diff.changes {
+                for line in change.differences() {
+                    writeln!(f, "    {line}")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for RegistrationDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+
+        let mut parts = Vec::new();
+        for (action, names) in [
+            ("added", &self.added),
+            ("changed", &self.changed),
+            ("removed", &self.removed),
+        ] {
+            if !names.is_empty() {
+                parts.push(format!("{action} {}", names.join(", ")));
+            }
+        }
+        write!(f, "{}", parts.join("; "))
+    }
+}
+
+/// An error while registering slash commands
+#[derive(Debug)]
+pub struct RegistrationError {
+    /// The guild commands were being registered to, `None` for global commands
+    pub guild_id: Option<GuildId>,
+    /// The command that failed to register, `None` if the error wasn't caused by a single command
+    pub command: Option<String>,
+    /// The error returned by discord
+    pub error: serenity::Error,
+}
+
+impl Error for RegistrationError {}
+
+impl Display for RegistrationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let target = match self.guild_id {
+            Some(g) => format!("guild {}", g.0),
+            None => "global".to_owned(),
+        };
+        match &self.command {
+            Some(cmd) => write!(f, "Failed to register `{cmd}` for {target}: {}", self.error),
+            None => write!(
+                f,
+                "Failed to register slash commands for {target}: {}",
+                self.error
+            ),
+        }
+    }
+}
+
+/// A function run when registering slash commands on Ready fails
+pub type RegistrationErrorHandler = fn(&RegistrationError);
+
+/// How failed registration requests are retried
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// How many times a request is tried before giving up
+    pub attempts: u32,
+    /// How long to wait before the first retry, doubled after each retry
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Whether a request could succeed if it was tried again
+///
+/// Server errors, rate limits and connection problems are retried, anything else would fail the same way again.
+pub(crate) fn is_transient(error: &serenity::Error) -> bool {
+    match error {
+        serenity::Error::Http(e) => match e.as_ref() {
+            HttpError::UnsuccessfulRequest(response) =>
+                response.status_code.is_server_error() || response.status_code.as_u16() == 429,
+            HttpError::Request(_) => true,
+            _ => false,
+        },
+        serenity::Error::Io(_) => true,
+        _ => false,
+    }
+}
+
+/// Runs a request, retrying it with backoff while it fails with transient errors
+pub(crate) async fn retry<T, F, Fut>(policy: RetryPolicy, mut request: F) -> serenity::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    let mut backoff = policy.backoff;
+    let mut attempt = 1;
+    loop {
+        match request().await {
+            Err(e) if attempt < policy.attempts && is_transient(&e) => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Compares the framework's commands against the registered ones
+///
+/// `local` are the serialized [Commands](crate::commands::Command) and `registered` the serialized ApplicationCommands returned by discord.<br>
+/// Commands are only added or updated if `register` is true and registered commands the framework doesn't have are only removed if `delete` is true,
+/// otherwise they are kept as they are.<br>
+/// Commands named in `protected` are never removed.
+pub fn diff_commands(
+    local: &[Value],
+    registered: &[Value],
+    register: bool,
+    delete: bool,
+    protected: &[String],
+) -> RegistrationDiff {
+    let mut diff = RegistrationDiff::default();
+
+    for reg in registered {
+        let name = command_name(reg);
+        match local.iter().find(|l| command_name(l) == name) {
+            Some(cmd) if register => {
+                let (before, after) = (normalize(reg), normalize(cmd));
+                if before != after {
+                    diff.changed.push(name.to_owned());
+                    diff.changes.push(CommandChange {
+                        name: name.to_owned(),
+                        before: Some(before),
+                        after: Some(after),
+                    });
+                }
+                diff.commands.push(cmd.clone());
+            }
+            None if delete && !protected.iter().any(|p| p == name) => {
+                diff.removed.push(name.to_owned());
+                diff.changes.push(CommandChange {
+                    name: name.to_owned(),
+                    before: Some(normalize(reg)),
+                    after: None,
+                });
+            }
+            _ => diff.commands.push(reg.clone()),
+        }
+    }
+
+    if register {
+        for cmd in local {
+            let name = command_name(cmd);
+            if !registered.iter().any(|r| command_name(r) == name) {
+                diff.added.push(name.to_owned());
+                diff.changes.push(CommandChange {
+                    name: name.to_owned(),
+                    before: None,
+                    after: Some(normalize(cmd)),
+                });
+                diff.commands.push(cmd.clone());
+            }
+        }
+    }
+
+    diff
+}
+
+fn command_name(cmd: &Value) -> &str {
+    cmd.get("name").and_then(Value::as_str).unwrap_or_default()
+}
+
+/// Strips a command down to the fields we send
+///
+/// Discord adds ids and versions to registered commands and leaves out empty or default fields,
+/// so they are removed or filled in before comparing.
+pub(crate) fn normalize(cmd: &Value) -> Value {
+    let mut output = Map::new();
+    output.insert("name".to_owned(), field(cmd, "name"));
+    output.insert("description".to_owned(), field(cmd, "description"));
+    output.insert("options".to_owned(), normalize_options(cmd));
+    Value::Object(output)
+}
+
+fn normalize_options(value: &Value) -> Value {
+    let options = value
+        .get("options")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|option| {
+            let mut output = Map::new();
+            output.insert("type".to_owned(), field(option, "type"));
+            output.insert("name".to_owned(), field(option, "name"));
+            output.insert("description".to_owned(), field(option, "description"));
+            output.insert(
+                "required".to_owned(),
+                Value::Bool(
+                    option
+                        .get("required")
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                ),
+            );
+            let choices = option
+                .get("choices")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|choice| {
+                    let mut output = Map::new();
+                    output.insert("name".to_owned(), field(choice, "name"));
+                    output.insert("value".to_owned(), field(choice, "value"));
+                    Value::Object(output)
+                })
+                .collect();
+            output.insert("choices".to_owned(), Value::Array(choices));
+            output.insert("min_value".to_owned(), field(option, "min_value"));
+            output.insert("max_value".to_owned(), field(option, "max_value"));
+            output.insert("options".to_owned(), normalize_options(option));
+            Value::Object(output)
+        })
+        .collect();
+
+    Value::Array(options)
+}
+
+fn field(value: &Value, key: &str) -> Value {
+    value.get(key).cloned().unwrap_or(Value::Null)
+}
+
+#[test]
+fn diff_test() {
+    use serde_json::json;
+
+    let local = vec![
+        json!({"name": "ping", "description": "pong", "options": []}),
+        json!({"name": "stats", "description": "get stats", "options": [
+            {"type": 6, "name": "user", "description": "the user", "required": true, "choices": null}
+        ]}),
+        json!({"name": "new", "description": "a new command", "options": []}),
+    ];
+    let registered = vec![
+        // Discord leaves out empty options and adds ids
+        json!({"id": "1", "version": "1", "type": 1, "name": "ping", "description": "pong"}),
+        json!({"id": "2", "version": "1", "type": 1, "name": "stats", "description": "get stats", "options": [
+            {"type": 6, "name": "user", "description": "the user"}
+        ]}),
+        json!({"id": "3", "version": "1", "type": 1, "name": "old", "description": "an old command"}),
+    ];
+
+    let diff = diff_commands(&local, &registered, true, true, &[]);
+    assert_eq!(diff.added, vec!["new"]);
+    assert_eq!(diff.changed, vec!["stats"]);
+    assert_eq!(diff.removed, vec!["old"]);
+    assert_eq!(diff.commands.len(), 3);
+    assert_eq!(diff.to_string(), "added new; changed stats; removed old");
+    let stats = diff.changes.iter().find(|c| c.name == "stats").unwrap();
+    assert_eq!(stats.differences(), vec![
+        "stats.options[0].required: false -> true"
+    ]);
+
+    // Unknown commands are kept when not deleting
+    let diff = diff_commands(&local, &registered, true, false, &[]);
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.commands.len(), 4);
+
+    // Protected commands are never removed
+    let diff = diff_commands(&local, &registered, true, true, &["old".to_owned()]);
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.commands.len(), 4);
+
+    // Nothing changes if everything matches
+    let diff = diff_commands(&local[.. 1], &registered[.. 1], true, true, &[]);
+    assert!(diff.is_empty());
+    assert_eq!(diff.to_string(), "no changes");
+
+    // Extra fields discord adds to choices aren't changes
+    let local = vec![
+        json!({"name": "size", "description": "pick a size", "options": [
+            {"type": 4, "name": "size", "description": "the size", "required": true, "choices": [
+                {"name": "small", "value": 1}
+            ]}
+        ]}),
+    ];
+    let registered = vec![
+        json!({"id": "4", "name": "size", "description": "pick a size", "options": [
+            {"type": 4, "name": "size", "description": "the size", "required": true, "choices": [
+                {"name": "small", "name_localizations": null, "value": 1}
+            ]}
+        ]}),
+    ];
+    assert!(diff_commands(&local, &registered, true, true, &[]).is_empty());
+}
+
+#[test]
+fn plan_display_test() {
+    use serde_json::json;
+
+    let local = vec![json!({"name": "ping", "description": "pong", "options": []})];
+    let registered = vec![json!({"name": "ping", "description": "ping"})];
+
+    let plan = RegistrationPlan {
+        global: Some(diff_commands(&local, &registered, true, false, &[])),
+        guilds: vec![(GuildId(5), diff_commands(&local, &local, true, false, &[]))],
+    };
+    assert!(!plan.is_empty());
+    assert_eq!(
+        plan.to_string(),
+        "global: changed ping\n    ping.description: \"ping\" -> \"pong\"\nguild 5: no changes\n"
+    );
+}
+
+#[tokio::test]
+async fn retry_test() {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    let policy = RetryPolicy {
+        attempts: 3,
+        backoff: Duration::ZERO,
+    };
+    let calls = AtomicU32::new(0);
+
+    // Transient errors are retried until the attempts run out
+    let result: serenity::Result<()> = retry(policy, || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset").into())
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+
+    // Other errors fail straight away
+    let result: serenity::Result<()> = retry(policy, || async {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Err(serenity::Error::Other("invalid command"))
+    })
+    .await;
+    assert!(result.is_err());
+    assert_eq!(calls.swap(0, Ordering::SeqCst), 1);
+
+    let result = retry(policy, || async {
+        match calls.fetch_add(1, Ordering::SeqCst) {
+            0 => Err(std::io::Error::new(std::io::ErrorKind::TimedOut, "timeout").into()),
+            _ => Ok(5),
+        }
+    })
+    .await;
+    assert_eq!(result.unwrap(), 5);
+}
@@ -1,193 +1,358 @@
-use std::sync::{Arc, Mutex as StdMutex, RwLock as StdRwLock};
+use std::sync::Arc;
 
 use serenity::{
-    futures::{lock::Mutex, FutureExt},
-    model::id::GuildId,
+    async_trait,
+    futures::lock::Mutex,
+    model::id::{ChannelId, GuildId},
     prelude::RwLock,
 };
 
+use crate::commands::{CommandResult, SlashyError};
+
+/// A settings provider loaded from a json file that can be reloaded while running.
+pub mod file;
+/// A settings provider that stores guild prefixes in a json file.
+pub mod prefix;
+
+/// Where a command was used
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// A channel in a guild
+    Guild(GuildId, ChannelId),
+    /// A dm channel
+    Dm(ChannelId),
+}
+
+impl Location {
+    /// Gets the guild if the location is in one
+    pub fn guild_id(&self) -> Option<GuildId> {
+        match self {
+            Location::Guild(guild_id, _) => Some(*guild_id),
+            Location::Dm(_) => None,
+        }
+    }
+
+    /// Gets the channel
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            Location::Guild(_, channel_id) | Location::Dm(channel_id) => *channel_id,
+        }
+    }
+}
+
 /// Allows users to define custom settings providers for the handler to pull from.
-pub trait SettingsProvider {
+///
+/// All methods are async so providers can be backed by a database or anything else that needs to be awaited.
+///
+/// Providers can be wrapped in an `Arc`, an `Arc<`[Mutex](serenity::futures::lock::Mutex)`<T>>` or a [RwLock](serenity::prelude::RwLock)`<T>`.
+/// `Arc<std::sync::Mutex<T>>` and `Arc<std::sync::RwLock<T>>` aren't providers as a std lock can't be held while the wrapped provider is awaited,
+/// switch to the async locks instead.
+#[async_trait]
+pub trait SettingsProvider: Send + Sync + 'static {
     /// The default prefixes the bot should fallback to.
-    fn default_prefixes(&self) -> Vec<String>;
-    /// The prefixes for a specific guild.
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>>;
+    async fn default_prefixes(&self) -> Vec<String>;
+    /// The prefixes for a specific guild, channel or dm.
+    async fn prefixes(&self, location: Location) -> Option<Vec<String>>;
+    /// Sets the prefixes for a specific guild.
+    ///
+    /// Used by the built in [prefix](crate::prefix) command, errors if the provider can't store the change.
+    async fn set_prefixes(&self, _guild_id: GuildId, _prefixes: Vec<String>) -> CommandResult {
+        Err(Box::new(SlashyError::new(
+            "The settings provider doesn't support changing prefixes",
+        )))
+    }
     /// Whether we should auto-register.
-    fn auto_register(&self) -> bool;
+    async fn auto_register(&self) -> bool;
     /// Whether we should auto-delete non-existant commands.
-    fn auto_delete(&self) -> bool;
+    async fn auto_delete(&self) -> bool;
+    /// Names of registered commands that are never auto-deleted.
+    ///
+    /// Useful for commands managed by another version of the bot.
+    async fn protected_commands(&self) -> Vec<String> {
+        Vec::new()
+    }
     /// Guilds to register commands to.
-    fn auto_register_guilds(&self) -> Vec<GuildId>;
+    async fn auto_register_guilds(&self) -> Vec<GuildId>;
+    /// Guilds a command with a [Dynamic](crate::commands::CommandScope::Dynamic) scope is registered to and can be used in.
+    async fn command_guilds(&self, _command: &str) -> Vec<GuildId> {
+        Vec::new()
+    }
+    /// Whether command and subcommand names in text commands should be matched ignoring case.
+    async fn case_insensitive(&self) -> bool {
+        false
+    }
+    /// Whether mentioning the bot can be used as a prefix.
+    async fn mention_prefix(&self) -> bool {
+        false
+    }
+    /// Whether there can be whitespace between the prefix and the command.
+    async fn prefix_whitespace(&self) -> bool {
+        false
+    }
+    /// Whether prefixes should be matched ignoring case.
+    async fn case_insensitive_prefixes(&self) -> bool {
+        false
+    }
+    /// Whether messages in dms are treated as commands without a prefix.
+    ///
+    /// Prefixes are still accepted and removed if they are used.
+    async fn no_dm_prefix(&self) -> bool {
+        false
+    }
+    /// Whether a command can be used in a channel of a guild.
+    ///
+    /// `path` is the command's name followed by any subcommands, eg. `stats get`.<br>
+    /// Every part of the path is checked so disabling `stats` also disables `stats get`.
+    async fn command_enabled(
+        &self,
+        _guild_id: GuildId,
+        _channel_id: ChannelId,
+        _path: &str,
+    ) -> bool {
+        true
+    }
+    /// Enables or disables a command for a whole guild, or only one channel if `channel_id` is given.
+    ///
+    /// Enabling a command in a channel should override it being disabled in the whole guild.<br>
+    /// Used by the built in [admin](crate::admin) commands, errors if the provider can't store the change.
+    async fn set_command_enabled(
+        &self,
+        _guild_id: GuildId,
+        _channel_id: Option<ChannelId>,
+        _path: &str,
+        _enabled: bool,
+    ) -> CommandResult {
+        Err(Box::new(SlashyError::new(
+            "The settings provider doesn't support enabling or disabling commands",
+        )))
+    }
 }
 
 /// Represents the settings for the framework
+///
+/// The default has no prefixes and every option turned off, set the fields you need and use `..Default::default()` for the rest.
+#[derive(Default)]
 pub struct Settings {
     /// The prefixes the bot uses
     pub prefixes: Vec<&'static str>,
     /// Whether to auto-register commands as slash commands on Ready.
     pub auto_register: bool,
     /// Whether to auto-delete unrecognized slash commands on Ready.
+    ///
+    /// Applies to the global commands and the commands in `slash_command_guilds`.
     pub auto_delete: bool,
+    /// Names of registered commands that are never auto-deleted.
+    pub protected_commands: Vec<&'static str>,
     /// Guilds to register slash commands to.
     ///
     /// Registers all commands to these guilds regardles of `auto_register`.<br>
     /// Is useful for quick updates or if you have `auto_register` off.
     pub slash_command_guilds: Vec<GuildId>,
+    /// Whether to match command and subcommand names in text commands ignoring case.
+    ///
+    /// Slash commands are unaffected as discord only allows lowercase names.
+    pub case_insensitive: bool,
+    /// Whether mentioning the bot can be used as a prefix.
+    pub mention_prefix: bool,
+    /// Whether to allow whitespace between the prefix and the command, eg. `! ping`.
+    pub prefix_whitespace: bool,
+    /// Whether to match prefixes ignoring case.
+    pub case_insensitive_prefixes: bool,
+    /// The prefixes used in dms, `None` uses the normal prefixes.
+    pub dm_prefixes: Option<Vec<&'static str>>,
+    /// Whether messages in dms are treated as commands without a prefix.
+    pub no_dm_prefix: bool,
 }
 
 
+#[async_trait]
 impl SettingsProvider for Settings {
-    fn default_prefixes(&self) -> Vec<String> {
+    async fn default_prefixes(&self) -> Vec<String> {
         self.prefixes.iter().map(|s| s.to_string()).collect()
     }
 
-    fn prefixes(&self, _guild_id: GuildId) -> Option<Vec<String>> {
-        Some(self.default_prefixes())
+    async fn prefixes(&self, location: Location) -> Option<Vec<String>> {
+        match (location, &self.dm_prefixes) {
+            (Location::Dm(_), Some(prefixes)) =>
+                Some(prefixes.iter().map(|s| s.to_string()).collect()),
+            _ => Some(self.default_prefixes().await),
+        }
     }
 
-    fn auto_register(&self) -> bool {
+    async fn auto_register(&self) -> bool {
         self.auto_register
     }
 
-    fn auto_delete(&self) -> bool {
+    async fn auto_delete(&self) -> bool {
         self.auto_delete
     }
 
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        self.slash_command_guilds.clone()
-    }
-}
-
-// Generic impls for commonly used wrapper types
-// Add more as needed but I don't think we should need more than Arc Mutex and RwLock currently
-
-
-impl<T: SettingsProvider + Send> SettingsProvider for Arc<Mutex<T>> {
-    fn default_prefixes(&self) -> Vec<String> {
-        async { self.lock().await.default_prefixes() }
-            .now_or_never()
-            .unwrap()
-    }
-
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>> {
-        async { self.lock().await.prefixes(guild_id) }
-            .now_or_never()
-            .unwrap()
+    async fn protected_commands(&self) -> Vec<String> {
+        self.protected_commands
+            .iter()
+            .map(|s| s.to_string())
+            .collect()
     }
 
-    fn auto_register(&self) -> bool {
-        async { self.lock().await.auto_register() }
-            .now_or_never()
-            .unwrap()
-    }
-
-    fn auto_delete(&self) -> bool {
-        async { self.lock().await.auto_delete() }
-            .now_or_never()
-            .unwrap()
-    }
-
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        async { self.lock().await.auto_register_guilds() }
-            .now_or_never()
-            .unwrap()
+    async fn auto_register_guilds(&self) -> Vec<GuildId> {
+        self.slash_command_guilds.clone()
     }
-}
 
-impl<T: SettingsProvider + Send> SettingsProvider for Arc<StdMutex<T>> {
-    fn default_prefixes(&self) -> Vec<String> {
-        T::default_prefixes(&self.lock().unwrap())
+    async fn case_insensitive(&self) -> bool {
+        self.case_insensitive
     }
 
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>> {
-        T::prefixes(&self.lock().unwrap(), guild_id)
+    async fn mention_prefix(&self) -> bool {
+        self.mention_prefix
     }
 
-    fn auto_register(&self) -> bool {
-        T::auto_register(&self.lock().unwrap())
+    async fn prefix_whitespace(&self) -> bool {
+        self.prefix_whitespace
     }
 
-    fn auto_delete(&self) -> bool {
-        T::auto_delete(&self.lock().unwrap())
+    async fn case_insensitive_prefixes(&self) -> bool {
+        self.case_insensitive_prefixes
     }
 
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        T::auto_register_guilds(&self.lock().unwrap())
+    async fn no_dm_prefix(&self) -> bool {
+        self.no_dm_prefix
     }
 }
 
-impl<T: SettingsProvider> SettingsProvider for Arc<T> {
-    fn default_prefixes(&self) -> Vec<String> {
-        T::default_prefixes(self)
-    }
-
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>> {
-        T::prefixes(self, guild_id)
-    }
-
-    fn auto_register(&self) -> bool {
-        T::auto_register(self)
-    }
-
-    fn auto_delete(&self) -> bool {
-        T::auto_delete(self)
-    }
-
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        T::auto_register_guilds(self)
-    }
+// Generic impls for commonly used wrapper types
+// Add more as needed but I don't think we should need more than Arc Mutex and RwLock currently
+// std locks can't be held across an await and the futures have to be Send, so only the async locks are supported
+
+/// Implements SettingsProvider for a wrapper by forwarding every method to the provider it wraps
+///
+/// `prefixes` and `set_prefixes` can be given in a block after the inner provider instead of being forwarded,
+/// for wrappers that store their own prefixes.
+macro_rules! forward_settings {
+    ($ty: ty, $this: ident => $inner: expr) => {
+        $crate::settings::forward_settings!($ty, $this => $inner, {
+            async fn prefixes(&self, location: Location) -> Option<Vec<String>> {
+                let $this = self;
+                $inner.prefixes(location).await
+            }
+
+            async fn set_prefixes(
+                &self,
+                guild_id: GuildId,
+                prefixes: Vec<String>,
+            ) -> CommandResult {
+                let $this = self;
+                $inner.set_prefixes(guild_id, prefixes).await
+            }
+        });
+    };
+    ($ty: ty, $this: ident => $inner: expr, { $($prefix_methods: tt)* }) => {
+        #[async_trait]
+        impl<T: SettingsProvider> SettingsProvider for $ty {
+            $($prefix_methods)*
+
+            async fn default_prefixes(&self) -> Vec<String> {
+                let $this = self;
+                $inner.default_prefixes().await
+            }
+
+            async fn auto_register(&self) -> bool {
+                let $this = self;
+                $inner.auto_register().await
+            }
+
+            async fn auto_delete(&self) -> bool {
+                let $this = self;
+                $inner.auto_delete().await
+            }
+
+            async fn protected_commands(&self) -> Vec<String> {
+                let $this = self;
+                $inner.protected_commands().await
+            }
+
+            async fn auto_register_guilds(&self) -> Vec<GuildId> {
+                let $this = self;
+                $inner.auto_register_guilds().await
+            }
+
+            async fn command_guilds(&self, command: &str) -> Vec<GuildId> {
+                let $this = self;
+                $inner.command_guilds(command).await
+            }
+
+            async fn case_insensitive(&self) -> bool {
+                let $this = self;
+                $inner.case_insensitive().await
+            }
+
+            async fn mention_prefix(&self) -> bool {
+                let $this = self;
+                $inner.mention_prefix().await
+            }
+
+            async fn prefix_whitespace(&self) -> bool {
+                let $this = self;
+                $inner.prefix_whitespace().await
+            }
+
+            async fn case_insensitive_prefixes(&self) -> bool {
+                let $this = self;
+                $inner.case_insensitive_prefixes().await
+            }
+
+            async fn no_dm_prefix(&self) -> bool {
+                let $this = self;
+                $inner.no_dm_prefix().await
+            }
+
+            async fn command_enabled(
+                &self,
+                guild_id: GuildId,
+                channel_id: ChannelId,
+                path: &str,
+            ) -> bool {
+                let $this = self;
+                $inner.command_enabled(guild_id, channel_id, path).await
+            }
+
+            async fn set_command_enabled(
+                &self,
+                guild_id: GuildId,
+                channel_id: Option<ChannelId>,
+                path: &str,
+                enabled: bool,
+            ) -> CommandResult {
+                let $this = self;
+                $inner
+                    .set_command_enabled(guild_id, channel_id, path, enabled)
+                    .await
+            }
+        }
+    };
 }
 
-impl<T: SettingsProvider> SettingsProvider for RwLock<T> {
-    fn default_prefixes(&self) -> Vec<String> {
-        async { self.read().await.default_prefixes() }
-            .now_or_never()
-            .unwrap()
-    }
+pub(crate) use forward_settings;
 
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>> {
-        async { self.read().await.prefixes(guild_id) }
-            .now_or_never()
-            .unwrap()
-    }
+forward_settings!(Arc<T>, this => this.as_ref());
+forward_settings!(Arc<Mutex<T>>, this => this.lock().await);
+forward_settings!(RwLock<T>, this => this.read().await);
 
-    fn auto_register(&self) -> bool {
-        async { self.read().await.auto_register() }
-            .now_or_never()
-            .unwrap()
-    }
+#[tokio::test]
+async fn contended_lock_test() {
+    use serenity::futures::FutureExt;
 
-    fn auto_delete(&self) -> bool {
-        async { self.read().await.auto_delete() }
-            .now_or_never()
-            .unwrap()
-    }
+    let settings = Arc::new(Mutex::new(Settings {
+        prefixes: vec!["!"],
+        case_insensitive: true,
+        ..Default::default()
+    }));
 
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        async { self.read().await.auto_register_guilds() }
-            .now_or_never()
-            .unwrap()
-    }
-}
+    let guard = settings.lock().await;
+    let mut prefixes = settings.default_prefixes();
+    // Waits for the lock instead of panicking
+    assert!((&mut prefixes).now_or_never().is_none());
+    drop(guard);
 
-impl<T: SettingsProvider> SettingsProvider for StdRwLock<T> {
-    fn default_prefixes(&self) -> Vec<String> {
-        T::default_prefixes(&self.read().unwrap())
-    }
-
-    fn prefixes(&self, guild_id: GuildId) -> Option<Vec<String>> {
-        T::prefixes(&self.read().unwrap(), guild_id)
-    }
-
-    fn auto_register(&self) -> bool {
-        T::auto_register(&self.read().unwrap())
-    }
-
-    fn auto_delete(&self) -> bool {
-        T::auto_delete(&self.read().unwrap())
-    }
-
-    fn auto_register_guilds(&self) -> Vec<GuildId> {
-        T::auto_register_guilds(&self.read().unwrap())
-    }
+    assert_eq!(prefixes.await, vec!["!".to_owned()]);
+    assert!(settings.case_insensitive().await);
 }
@@ -0,0 +1,488 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    error::Error,
+    fmt::Display,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use serde::{Deserialize, Serialize};
+use serenity::{
+    async_trait,
+    model::id::{ChannelId, GuildId},
+    prelude::RwLock,
+};
+use tokio::task::JoinHandle;
+
+use crate::{
+    commands::CommandResult,
+    settings::{Location, SettingsProvider},
+};
+
+/// The contents of a settings file
+///
+/// Every field is optional in the file and falls back to its default
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct SettingsFile {
+    /// The prefixes used when a guild doesn't have its own
+    pub prefixes: Vec<String>,
+    /// The prefixes for specific guilds
+    pub guild_prefixes: HashMap<u64, Vec<String>>,
+    /// The prefixes used in dms
+    pub dm_prefixes: Option<Vec<String>>,
+    /// Whether messages in dms are treated as commands without a prefix.
+    pub no_dm_prefix: bool,
+    /// Whether to auto-register commands as slash commands on Ready.
+    pub auto_register: bool,
+    /// Whether to auto-delete unrecognized slash commands on Ready.
+    pub auto_delete: bool,
+    /// Names of registered commands that are never auto-deleted.
+    pub protected_commands: Vec<String>,
+    /// Guilds to register slash commands to.
+    pub slash_command_guilds: Vec<u64>,
+    /// Guilds each command with a dynamic scope is registered to.
+    pub command_guilds: HashMap<String, Vec<u64>>,
+    /// Whether to match command and subcommand names in text commands ignoring case.
+    pub case_insensitive: bool,
+    /// Whether mentioning the bot can be used as a prefix.
+    pub mention_prefix: bool,
+    /// Whether to allow whitespace between the prefix and the command.
+    pub prefix_whitespace: bool,
+    /// Whether to match prefixes ignoring case.
+    pub case_insensitive_prefixes: bool,
+    /// The commands disabled in each guild
+    pub disabled_commands: HashMap<u64, DisabledCommands>,
+}
+
+/// The commands disabled in a guild
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct DisabledCommands {
+    /// Commands disabled in the whole guild
+    pub guild: BTreeSet<String>,
+    /// Commands disabled in specific channels
+    pub channels: HashMap<u64, BTreeSet<String>>,
+    /// Commands enabled in specific channels while they are disabled in the whole guild
+    pub enabled_channels: HashMap<u64, BTreeSet<String>>,
+}
+
+/// The error returned when a settings file can't be read or written
+#[derive(Debug)]
+pub enum SettingsFileError {
+    /// The file couldn't be read or written
+    Io(std::io::Error),
+    /// The file isn't valid settings json
+    Parse(serde_json::Error),
+}
+
+impl Error for SettingsFileError {}
+
+impl Display for SettingsFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettingsFileError::Io(e) => write!(f, "Could not access settings file: {e}"),
+            SettingsFileError::Parse(e) => write!(f, "Invalid settings file: {e}"),
+        }
+    }
+}
+
+impl From<std::io::Error> for SettingsFileError {
+    fn from(e: std::io::Error) -> Self {
+        SettingsFileError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SettingsFileError {
+    fn from(e: serde_json::Error) -> Self {
+        SettingsFileError::Parse(e)
+    }
+}
+
+/// A [SettingsProvider] that loads its settings from a json file
+///
+/// The file can be reloaded while the bot is running, either by calling [reload](FileSettings::reload) or by starting a [watch](FileSettings::watch).<br>
+/// Changes made through the provider, like disabling commands or setting prefixes, are written back to the file.
+/// ```no_run
+/// # use std::time::Duration;
+/// # use slashy::settings::file::FileSettings;
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let settings = FileSettings::load("settings.json").await?;
+/// settings.watch(Duration::from_secs(5), |e| eprintln!("Couldn't reload the settings: {e}"));
+/// // Pass `settings` to Framework::new
+/// # Ok(())
+/// # }
+/// ```
+pub struct FileSettings {
+    path: PathBuf,
+    // Swapped out as a whole so readers never see a half loaded file
+    settings: RwLock<Arc<SettingsFile>>,
+    modified: RwLock<Option<SystemTime>>,
+}
+
+impl FileSettings {
+    /// Loads the settings from a file
+    pub async fn load<P: AsRef<Path>>(path: P) -> Result<Arc<Self>, SettingsFileError> {
+        let path = path.as_ref().to_owned();
+        let (settings, modified) = read_file(&path).await?;
+
+        Ok(Arc::new(FileSettings {
+            path,
+            settings: RwLock::new(Arc::new(settings)),
+            modified: RwLock::new(modified),
+        }))
+    }
+
+    /// Gets the currently loaded settings
+    pub async fn current(&self) -> Arc<SettingsFile> {
+        self.settings.read().await.clone()
+    }
+
+    /// Reads the file again if it has changed since it was last read
+    ///
+    /// Returns whether the settings were reloaded.<br>
+    /// If the file is invalid the current settings are kept.
+    pub async fn reload(&self) -> Result<bool, SettingsFileError> {
+        let modified = tokio::fs::metadata(&self.path).await?.modified().ok();
+        if modified.is_some() && modified == *self.modified.read().await {
+            return Ok(false);
+        }
+
+        // Only recorded once the file parsed so a half written file is read again
+        let (settings, modified) = read_file(&self.path).await?;
+        *self.settings.write().await = Arc::new(settings);
+        *self.modified.write().await = modified;
+        Ok(true)
+    }
+
+    /// Starts a task that checks the file for changes every `interval`
+    ///
+    /// Errors while reloading are passed to `on_error` and the current settings are kept.
+    pub fn watch<E>(self: &Arc<Self>, interval: Duration, on_error: E) -> JoinHandle<()>
+    where E: Fn(SettingsFileError) + Send + 'static {
+        let settings = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = settings.reload().await {
+                    on_error(e);
+                }
+            }
+        })
+    }
+
+    /// Changes the settings and writes them back to the file
+    ///
+    /// If the file was edited since it was last read the change is made to the edited file instead
+    async fn update<F: FnOnce(&mut SettingsFile)>(&self, f: F) -> Result<(), SettingsFileError> {
+        let mut current = self.settings.write().await;
+        let on_disk = match tokio::fs::metadata(&self.path).await {
+            Ok(metadata) => metadata.modified().ok(),
+            Err(_) => None,
+        };
+        let mut settings = if on_disk.is_some() && on_disk != *self.modified.read().await {
+            read_file(&self.path).await?.0
+        } else {
+            current.as_ref().clone()
+        };
+        f(&mut settings);
+
+        write_json(&self.path, &settings).await?;
+
+        *current = Arc::new(settings);
+        *self.modified.write().await = tokio::fs::metadata(&self.path).await?.modified().ok();
+        Ok(())
+    }
+}
+
+/// Writes a value to a json file
+pub(crate) async fn write_json<T: Serialize>(
+    path: &Path,
+    value: &T,
+) -> Result<(), SettingsFileError> {
+    // Write to a temporary file first so the file is never left half written
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!("{file_name}.tmp"));
+    tokio::fs::write(&temp, serde_json::to_string_pretty(value)?).await?;
+    tokio::fs::rename(&temp, path).await?;
+    Ok(())
+}
+
+async fn read_file(path: &Path) -> Result<(SettingsFile, Option<SystemTime>), SettingsFileError> {
+    let modified = tokio::fs::metadata(path).await?.modified().ok();
+    let content = tokio::fs::read_to_string(path).await?;
+    Ok((serde_json::from_str(&content)?, modified))
+}
+
+#[async_trait]
+impl SettingsProvider for FileSettings {
+    async fn default_prefixes(&self) -> Vec<String> {
+        self.current().await.prefixes.clone()
+    }
+
+    async fn prefixes(&self, location: Location) -> Option<Vec<String>> {
+        let settings = self.current().await;
+        match location {
+            Location::Guild(guild_id, _) => settings.guild_prefixes.get(&guild_id.0).cloned(),
+            Location::Dm(_) => settings.dm_prefixes.clone(),
+        }
+    }
+
+    async fn auto_register(&self) -> bool {
+        self.current().await.auto_register
+    }
+
+    async fn auto_delete(&self) -> bool {
+        self.current().await.auto_delete
+    }
+
+    async fn protected_commands(&self) -> Vec<String> {
+        self.current().await.protected_commands.clone()
+    }
+
+    async fn auto_register_guilds(&self) -> Vec<GuildId> {
+        self.current()
+            .await
+            .slash_command_guilds
+            .iter()
+            .map(|g| GuildId(*g))
+            .collect()
+    }
+
+    async fn command_guilds(&self, command: &str) -> Vec<GuildId> {
+        self.current()
+            .await
+            .command_guilds
+            .get(command)
+            .into_iter()
+            .flatten()
+            .map(|g| GuildId(*g))
+            .collect()
+    }
+
+    async fn case_insensitive(&self) -> bool {
+        self.current().await.case_insensitive
+    }
+
+    async fn set_prefixes(&self, guild_id: GuildId, prefixes: Vec<String>) -> CommandResult {
+        self.update(|settings| {
+            settings.guild_prefixes.insert(guild_id.0, prefixes);
+        })
+        .await?;
+        Ok(())
+    }
+
+    async fn mention_prefix(&self) -> bool {
+        self.current().await.mention_prefix
+    }
+
+    async fn prefix_whitespace(&self) -> bool {
+        self.current().await.prefix_whitespace
+    }
+
+    async fn case_insensitive_prefixes(&self) -> bool {
+        self.current().await.case_insensitive_prefixes
+    }
+
+    async fn no_dm_prefix(&self) -> bool {
+        self.current().await.no_dm_prefix
+    }
+
+    async fn command_enabled(&self, guild_id: GuildId, channel_id: ChannelId, path: &str) -> bool {
+        let settings = self.current().await;
+        let disabled = match settings.disabled_commands.get(&guild_id.0) {
+            Some(d) => d,
+            None => return true,
+        };
+        let in_channel = |channels: &HashMap<u64, BTreeSet<String>>| {
+            channels
+                .get(&channel_id.0)
+                .map(|c| c.contains(path))
+                .unwrap_or(false)
+        };
+
+        // Channel settings take precedence over the guild's
+        if in_channel(&disabled.enabled_channels) {
+            true
+        } else {
+            !in_channel(&disabled.channels) && !disabled.guild.contains(path)
+        }
+    }
+
+    async fn set_command_enabled(
+        &self,
+        guild_id: GuildId,
+        channel_id: Option<ChannelId>,
+        path: &str,
+        enabled: bool,
+    ) -> CommandResult {
+        self.update(|settings| {
+            let disabled = settings.disabled_commands.entry(guild_id.0).or_default();
+            match channel_id {
+                Some(c) => {
+                    let overridden = enabled && disabled.guild.contains(path);
+                    let channel = disabled.channels.entry(c.0).or_default();
+                    let allowed = disabled.enabled_channels.entry(c.0).or_default();
+                    if enabled {
+                        channel.remove(path);
+                    } else {
+                        channel.insert(path.to_owned());
+                    }
+                    if overridden {
+                        allowed.insert(path.to_owned());
+                    } else {
+                        allowed.remove(path);
+                    }
+                }
+                None => {
+                    // Channel overrides only made sense against the old guild setting
+                    for allowed in disabled.enabled_channels.values_mut() {
+                        allowed.remove(path);
+                    }
+                    if enabled {
+                        disabled.guild.remove(path);
+                    } else {
+                        disabled.guild.insert(path.to_owned());
+                    }
+                }
+            }
+        })
+        .await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn file_settings_test() {
+    let path = std::env::temp_dir().join(format!("slashy_settings_{}.json", std::process::id()));
+    tokio::fs::write(
+        &path,
+        r#"{"prefixes": ["!"], "guild_prefixes": {"1": ["?"]}, "dm_prefixes": ["."], "case_insensitive": true}"#,
+    )
+    .await
+    .unwrap();
+
+    let settings = FileSettings::load(&path).await.unwrap();
+    assert_eq!(settings.default_prefixes().await, vec!["!".to_owned()]);
+    assert_eq!(
+        settings
+            .prefixes(Location::Guild(GuildId(1), ChannelId(1)))
+            .await,
+        Some(vec!["?".to_owned()])
+    );
+    assert_eq!(
+        settings
+            .prefixes(Location::Guild(GuildId(2), ChannelId(1)))
+            .await,
+        None
+    );
+    assert_eq!(
+        settings.prefixes(Location::Dm(ChannelId(1))).await,
+        Some(vec![".".to_owned()])
+    );
+    assert!(settings.case_insensitive().await);
+    assert!(!settings.auto_register().await);
+
+    // Disabling is written back to the file
+    settings
+        .set_command_enabled(GuildId(1), Some(ChannelId(5)), "meme", false)
+        .await
+        .unwrap();
+    assert!(
+        !settings
+            .command_enabled(GuildId(1), ChannelId(5), "meme")
+            .await
+    );
+    assert!(
+        settings
+            .command_enabled(GuildId(1), ChannelId(6), "meme")
+            .await
+    );
+    let reloaded = FileSettings::load(&path).await.unwrap();
+    assert_eq!(reloaded.current().await, settings.current().await);
+
+    // Invalid files keep the old settings
+    tokio::fs::write(&path, "{ not json").await.unwrap();
+    assert!(matches!(
+        settings.reload().await,
+        Err(SettingsFileError::Parse(_))
+    ));
+    assert_eq!(settings.default_prefixes().await, vec!["!".to_owned()]);
+
+    tokio::fs::write(&path, r#"{"prefixes": ["$"]}"#)
+        .await
+        .unwrap();
+    assert!(settings.reload().await.unwrap());
+    assert_eq!(settings.default_prefixes().await, vec!["$".to_owned()]);
+
+    // Edits made since the last reload aren't lost by a change
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    tokio::fs::write(&path, r#"{"prefixes": ["%"]}"#)
+        .await
+        .unwrap();
+    settings
+        .set_command_enabled(GuildId(1), None, "meme", false)
+        .await
+        .unwrap();
+    assert_eq!(settings.default_prefixes().await, vec!["%".to_owned()]);
+    let reloaded = FileSettings::load(&path).await.unwrap();
+    assert_eq!(reloaded.current().await, settings.current().await);
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn channel_override_test() {
+    let path = std::env::temp_dir().join(format!("slashy_override_{}.json", std::process::id()));
+    tokio::fs::write(&path, "{}").await.unwrap();
+    let settings = FileSettings::load(&path).await.unwrap();
+
+    settings
+        .set_command_enabled(GuildId(1), None, "meme", false)
+        .await
+        .unwrap();
+    settings
+        .set_command_enabled(GuildId(1), Some(ChannelId(5)), "meme", true)
+        .await
+        .unwrap();
+    assert!(
+        settings
+            .command_enabled(GuildId(1), ChannelId(5), "meme")
+            .await
+    );
+    assert!(
+        !settings
+            .command_enabled(GuildId(1), ChannelId(6), "meme")
+            .await
+    );
+
+    // Disabling the channel again removes the override
+    settings
+        .set_command_enabled(GuildId(1), Some(ChannelId(5)), "meme", false)
+        .await
+        .unwrap();
+    assert!(
+        !settings
+            .command_enabled(GuildId(1), ChannelId(5), "meme")
+            .await
+    );
+
+    // So does a later guild wide disable
+    settings
+        .set_command_enabled(GuildId(1), Some(ChannelId(6)), "meme", true)
+        .await
+        .unwrap();
+    settings
+        .set_command_enabled(GuildId(1), None, "meme", false)
+        .await
+        .unwrap();
+    assert!(
+        !settings
+            .command_enabled(GuildId(1), ChannelId(6), "meme")
+            .await
+    );
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
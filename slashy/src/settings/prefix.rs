@@ -0,0 +1,136 @@
+use std::{
+    collections::HashMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use serenity::{
+    async_trait,
+    model::id::{ChannelId, GuildId},
+    prelude::RwLock,
+};
+
+use crate::{
+    commands::CommandResult,
+    settings::{
+        file::{write_json, SettingsFileError},
+        forward_settings,
+        Location,
+        SettingsProvider,
+    },
+};
+
+/// A [SettingsProvider] that stores the prefixes of each guild in a json file
+///
+/// Wraps another provider which every other setting, and the prefixes of guilds without their own, are taken from.
+/// ```no_run
+/// # use slashy::settings::{Settings, prefix::PrefixStore};
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let settings = Settings {
+///     prefixes: vec!["!"],
+///     auto_register: true,
+///     auto_delete: true,
+///     ..Default::default()
+/// };
+/// let settings = PrefixStore::load(settings, "prefixes.json").await?;
+/// // Pass `settings` to Framework::new
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrefixStore<T: SettingsProvider> {
+    inner: T,
+    path: PathBuf,
+    prefixes: RwLock<HashMap<u64, Vec<String>>>,
+}
+
+impl<T: SettingsProvider> PrefixStore<T> {
+    /// Loads the stored prefixes from a file
+    ///
+    /// The file is created the first time a prefix is set if it doesn't exist.
+    pub async fn load<P: AsRef<Path>>(inner: T, path: P) -> Result<Self, SettingsFileError> {
+        let path = path.as_ref().to_owned();
+        let prefixes = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => serde_json::from_str(&content)?,
+            Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(PrefixStore {
+            inner,
+            path,
+            prefixes: RwLock::new(prefixes),
+        })
+    }
+}
+
+forward_settings!(PrefixStore<T>, this => this.inner, {
+    async fn prefixes(&self, location: Location) -> Option<Vec<String>> {
+        let stored = match location {
+            Location::Guild(guild_id, _) => self.prefixes.read().await.get(&guild_id.0).cloned(),
+            Location::Dm(_) => None,
+        };
+
+        match stored {
+            Some(prefixes) => Some(prefixes),
+            None => self.inner.prefixes(location).await,
+        }
+    }
+
+    async fn set_prefixes(&self, guild_id: GuildId, prefixes: Vec<String>) -> CommandResult {
+        let mut stored = self.prefixes.write().await;
+        let mut updated = stored.clone();
+        updated.insert(guild_id.0, prefixes);
+
+        write_json(&self.path, &updated).await?;
+        *stored = updated;
+        Ok(())
+    }
+});
+
+#[tokio::test]
+async fn prefix_store_test() {
+    use crate::settings::Settings;
+
+    let path = std::env::temp_dir().join(format!("slashy_prefixes_{}.json", std::process::id()));
+    let settings = || Settings {
+        prefixes: vec!["!"],
+        ..Default::default()
+    };
+
+    let store = PrefixStore::load(settings(), &path).await.unwrap();
+    assert_eq!(
+        store
+            .prefixes(Location::Guild(GuildId(1), ChannelId(1)))
+            .await,
+        Some(vec!["!".to_owned()])
+    );
+
+    store
+        .set_prefixes(GuildId(1), vec!["?".to_owned(), "$".to_owned()])
+        .await
+        .unwrap();
+    assert_eq!(
+        store
+            .prefixes(Location::Guild(GuildId(1), ChannelId(1)))
+            .await,
+        Some(vec!["?".to_owned(), "$".to_owned()])
+    );
+    // Other guilds still use the wrapped provider
+    assert_eq!(
+        store
+            .prefixes(Location::Guild(GuildId(2), ChannelId(1)))
+            .await,
+        Some(vec!["!".to_owned()])
+    );
+
+    // The prefixes are kept between loads
+    let store = PrefixStore::load(settings(), &path).await.unwrap();
+    assert_eq!(
+        store
+            .prefixes(Location::Guild(GuildId(1), ChannelId(1)))
+            .await,
+        Some(vec!["?".to_owned(), "$".to_owned()])
+    );
+
+    tokio::fs::remove_file(&path).await.unwrap();
+}
@@ -0,0 +1,420 @@
+use std::fmt::Display;
+
+use regex::Regex;
+use serde_json::Value;
+
+pub use slashy_limits::{
+    MAX_CHOICES,
+    MAX_COMMANDS,
+    MAX_COMMAND_LENGTH,
+    MAX_DESCRIPTION_LENGTH,
+    MAX_NAME_LENGTH,
+    MAX_OPTIONS,
+};
+
+const SUB_COMMAND: u64 = 1;
+const SUB_COMMAND_GROUP: u64 = 2;
+const STRING: u64 = 3;
+const INTEGER: u64 = 4;
+const NUMBER: u64 = 10;
+
+/// Counts the characters in a description
+///
+/// Used by [command](crate::command) to check descriptions taken from a function's doc comment while compiling.
+#[doc(hidden)]
+pub const fn description_length(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut length = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // Continuation bytes are part of the previous character
+        if bytes[i] & 0xC0 != 0x80 {
+            length += 1;
+        }
+        i += 1;
+    }
+    length
+}
+
+/// A rule of discord's that a command breaks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The path to the field that breaks the rule, eg. `stats.options[0].name`
+    pub path: String,
+    /// Which rule was broken
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// Checks serialized commands against discord's rules for slash commands without registering them
+///
+/// `commands` should be everything registered in one place, globally or to a guild, so names can be checked for duplicates.<br>
+/// Returns every rule that is broken, an empty list means discord should accept the commands.
+/// ```
+/// # use serde_json::json;
+/// # use slashy::validation::validate_commands;
+/// let violations = validate_commands(&[json!({
+///     "name": "Ping",
+///     "description": "ping pong",
+/// })]);
+/// assert_eq!(violations[0].to_string(), "Ping.name: must be lowercase");
+/// ```
+pub fn validate_commands(commands: &[Value]) -> Vec<Violation> {
+    let mut violations = validate_command_set(commands);
+    for cmd in commands {
+        violations.extend(validate_command(cmd));
+    }
+    violations
+}
+
+/// Checks the rules that apply to everything registered in one place, the number of commands and duplicate names
+///
+/// Unlike [validate_commands] the commands themselves aren't checked.
+pub fn validate_command_set(commands: &[Value]) -> Vec<Violation> {
+    let mut violations = Vec::new();
+
+    if commands.len() > MAX_COMMANDS {
+        violations.push(Violation {
+            path: String::new(),
+            message: format!(
+                "{} commands is more than the limit of {MAX_COMMANDS}",
+                commands.len()
+            ),
+        });
+    }
+    for name in duplicates(commands) {
+        violations.push(Violation {
+            path: name.to_owned(),
+            message: "the command name is used more than once".to_owned(),
+        });
+    }
+    violations
+}
+
+/// Checks a single serialized command against discord's rules for slash commands
+///
+/// Returns every rule that is broken.
+pub fn validate_command(cmd: &Value) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let path = string(cmd, "name").to_owned();
+
+    check_name(&path, cmd, &mut violations);
+    check_description(&path, cmd, &mut violations);
+    check_options(&path, cmd, None, &mut violations);
+
+    let length = command_length(cmd);
+    if length > MAX_COMMAND_LENGTH {
+        violations.push(Violation {
+            path,
+            message: format!(
+                "names, descriptions and choices total {length} characters, more than the limit \
+                 of {MAX_COMMAND_LENGTH}"
+            ),
+        });
+    }
+
+    violations
+}
+
+/// Checks the options of a command or option
+///
+/// `parent` is the type of the option they belong to, `None` for the command itself
+fn check_options(path: &str, value: &Value, parent: Option<u64>, violations: &mut Vec<Violation>) {
+    let options = array(value, "options");
+    let mut violation = |message: String| {
+        violations.push(Violation {
+            path: format!("{path}.options"),
+            message,
+        })
+    };
+
+    if options.len() > MAX_OPTIONS {
+        violation(format!(
+            "{} options is more than the limit of {MAX_OPTIONS}",
+            options.len()
+        ));
+    }
+
+    let subcommands = options
+        .iter()
+        .filter(|o| matches!(option_type(o), SUB_COMMAND | SUB_COMMAND_GROUP))
+        .count();
+    match parent {
+        Some(SUB_COMMAND_GROUP) if subcommands != options.len() =>
+            violation("SubCommandGroups can only contain SubCommands".to_owned()),
+        Some(SUB_COMMAND) if subcommands > 0 =>
+            violation("SubCommands can't contain SubCommands or SubCommandGroups".to_owned()),
+        _ if subcommands > 0 && subcommands != options.len() => violation(
+            "SubCommands and SubCommandGroups can't be mixed with other options".to_owned(),
+        ),
+        _ => {}
+    }
+    if parent == Some(SUB_COMMAND_GROUP)
+        && options.iter().any(|o| option_type(o) == SUB_COMMAND_GROUP)
+    {
+        violation("SubCommandGroups can't be nested".to_owned());
+    }
+
+    for name in duplicates(options) {
+        violations.push(Violation {
+            path: format!("{path}.options"),
+            message: format!("the name `{name}` is used more than once"),
+        });
+    }
+
+    let mut seen_optional = false;
+    for (i, option) in options.iter().enumerate() {
+        let path = format!("{path}.options[{i}]");
+        check_name(&path, option, violations);
+        check_description(&path, option, violations);
+
+        let required = option
+            .get("required")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        if required && seen_optional {
+            violations.push(Violation {
+                path: format!("{path}.required"),
+                message: "required options must come before optional ones".to_owned(),
+            });
+        }
+        seen_optional |= !required;
+
+        check_choices(&path, option, violations);
+        if matches!(option_type(option), SUB_COMMAND | SUB_COMMAND_GROUP) {
+            check_options(&path, option, Some(option_type(option)), violations);
+        }
+    }
+}
+
+fn check_choices(path: &str, option: &Value, violations: &mut Vec<Violation>) {
+    let choices = array(option, "choices");
+    if choices.is_empty() {
+        return;
+    }
+
+    if !matches!(option_type(option), STRING | INTEGER | NUMBER) {
+        violations.push(Violation {
+            path: format!("{path}.choices"),
+            message: "only String, Integer and Number options can have choices".to_owned(),
+        });
+    }
+    if choices.len() > MAX_CHOICES {
+        violations.push(Violation {
+            path: format!("{path}.choices"),
+            message: format!(
+                "{} choices is more than the limit of {MAX_CHOICES}",
+                choices.len()
+            ),
+        });
+    }
+
+    for (i, choice) in choices.iter().enumerate() {
+        let path = format!("{path}.choices[{i}]");
+        check_length(
+            &format!("{path}.name"),
+            string(choice, "name"),
+            MAX_DESCRIPTION_LENGTH,
+            violations,
+        );
+        if let Some(value) = choice.get("value").and_then(Value::as_str) {
+            check_length(
+                &format!("{path}.value"),
+                value,
+                MAX_DESCRIPTION_LENGTH,
+                violations,
+            );
+        }
+    }
+}
+
+fn check_name(path: &str, value: &Value, violations: &mut Vec<Violation>) {
+    lazy_static::lazy_static! {
+        static ref NAME: Regex = Regex::new(r"^[-_\p{L}\p{N}\p{sc=Deva}\p{sc=Thai}]{1,32}$").unwrap();
+    }
+
+    let name = string(value, "name");
+    let path = format!("{path}.name");
+    let length = name.chars().count();
+    if length == 0 || length > MAX_NAME_LENGTH {
+        violations.push(Violation {
+            path,
+            message: format!("must be 1 to {MAX_NAME_LENGTH} characters long, is {length}"),
+        });
+    } else if !NAME.is_match(name) {
+        violations.push(Violation {
+            path,
+            message: "can only contain letters, numbers, `-` and `_`".to_owned(),
+        });
+    } else if name.chars().any(char::is_uppercase) {
+        violations.push(Violation {
+            path,
+            message: "must be lowercase".to_owned(),
+        });
+    }
+}
+
+fn check_description(path: &str, value: &Value, violations: &mut Vec<Violation>) {
+    check_length(
+        &format!("{path}.description"),
+        string(value, "description"),
+        MAX_DESCRIPTION_LENGTH,
+        violations,
+    );
+}
+
+fn check_length(path: &str, text: &str, max: usize, violations: &mut Vec<Violation>) {
+    let length = text.chars().count();
+    if length == 0 || length > max {
+        violations.push(Violation {
+            path: path.to_owned(),
+            message: format!("must be 1 to {max} characters long, is {length}"),
+        });
+    }
+}
+
+/// The names used more than once, listed once for every repeat
+fn duplicates(values: &[Value]) -> impl Iterator<Item = &str> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(i, value)| {
+            values[.. *i]
+                .iter()
+                .any(|v| string(v, "name") == string(value, "name"))
+        })
+        .map(|(_, value)| string(value, "name"))
+}
+
+/// The combined length of every name, description and choice in a command
+fn command_length(value: &Value) -> usize {
+    let own = string(value, "name").chars().count() + string(value, "description").chars().count();
+    let choices = array(value, "choices")
+        .iter()
+        .map(|c| {
+            let value = match c.get("value") {
+                Some(Value::String(s)) => s.chars().count(),
+                Some(v) => v.to_string().len(),
+                None => 0,
+            };
+            string(c, "name").chars().count() + value
+        })
+        .sum::<usize>();
+    let options = array(value, "options")
+        .iter()
+        .map(command_length)
+        .sum::<usize>();
+
+    own + choices + options
+}
+
+fn option_type(option: &Value) -> u64 {
+    option
+        .get("type")
+        .and_then(Value::as_u64)
+        .unwrap_or_default()
+}
+
+fn string<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+fn array<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+#[test]
+fn validate_test() {
+    use serde_json::json;
+
+    let valid = json!({
+        "name": "stats",
+        "description": "get or set a user's stats",
+        "options": [
+            {"type": 2, "name": "get", "description": "get info about stats", "options": [
+                {"type": 1, "name": "points", "description": "get a user's points", "options": [
+                    {"type": 6, "name": "user", "description": "the user", "required": true}
+                ]}
+            ]},
+            {"type": 1, "name": "set", "description": "set a user's stats"}
+        ]
+    });
+    assert_eq!(validate_commands(std::slice::from_ref(&valid)), vec![]);
+
+    let invalid = json!({
+        "name": "Grid",
+        "description": "",
+        "options": [
+            {"type": 4, "name": "size", "description": "the size", "choices": [
+                {"name": "small", "value": 1}
+            ]},
+            {"type": 3, "name": "bad name", "description": "the name", "required": true},
+            {"type": 5, "name": "size", "description": "a duplicate", "choices": [
+                {"name": "yes", "value": true}
+            ]}
+        ]
+    });
+    let violations = validate_command(&invalid)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(violations, vec![
+        "Grid.name: must be lowercase",
+        "Grid.description: must be 1 to 100 characters long, is 0",
+        "Grid.options: the name `size` is used more than once",
+        "Grid.options[1].name: can only contain letters, numbers, `-` and `_`",
+        "Grid.options[1].required: required options must come before optional ones",
+        "Grid.options[2].choices: only String, Integer and Number options can have choices",
+    ]);
+
+    let nested = json!({
+        "name": "nested",
+        "description": "too deep",
+        "options": [
+            {"type": 1, "name": "sub", "description": "a subcommand", "options": [
+                {"type": 1, "name": "inner", "description": "another subcommand"}
+            ]},
+            {"type": 3, "name": "text", "description": "mixed in"}
+        ]
+    });
+    let violations = validate_command(&nested)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(violations, vec![
+        "nested.options: SubCommands and SubCommandGroups can't be mixed with other options",
+        "nested.options[0].options: SubCommands can't contain SubCommands or SubCommandGroups",
+    ]);
+
+    let options = (0 .. 26)
+        .map(|i| {
+            json!({"type": 3, "name": format!("o{i}"), "description": "d".repeat(100), "choices": [
+                {"name": "c".repeat(100), "value": "v".repeat(100)},
+                {"name": "b".repeat(100), "value": "w".repeat(100)}
+            ]})
+        })
+        .collect::<Vec<_>>();
+    let long = json!({"name": "long", "description": "many options", "options": options});
+    let violations = validate_command(&long)
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(violations, vec![
+        "long.options: 26 options is more than the limit of 25",
+        "long: names, descriptions and choices total 13084 characters, more than the limit of 8000"
+    ]);
+
+    assert_eq!(
+        validate_commands(&[valid.clone(), valid])[0].to_string(),
+        "stats: the command name is used more than once"
+    );
+}
@@ -36,12 +36,16 @@ fn interaction_parse_test() {
                 name: "get",
                 description: "",
                 required: false,
+                aliases: None,
+                cooldown: None,
                 func: None,
                 options: Some(vec![
                     CommandArguments::SubCommand {
                         name: "points",
                         description: "get a user's points",
                         required: false,
+                        aliases: None,
+                        cooldown: None,
                         func: Some(test),
                         options: Some(vec![CommandArguments::User {
                             name: "user",
@@ -53,6 +57,8 @@ fn interaction_parse_test() {
                         name: "leaderboard",
                         description: "get the guild leaderboard",
                         required: false,
+                        aliases: None,
+                        cooldown: None,
                         func: Some(test2),
                         options: Some(vec![CommandArguments::Integer {
                             name: "page",
@@ -62,6 +68,8 @@ fn interaction_parse_test() {
                                 name: "default",
                                 value: 0,
                             }]),
+                            min_value: None,
+                            max_value: None,
                         }]),
                     },
                 ]),
@@ -70,6 +78,8 @@ fn interaction_parse_test() {
                 name: "self",
                 description: "get your personal stats",
                 required: false,
+                aliases: None,
+                cooldown: None,
                 options: None,
                 func: Some(test3),
             },
@@ -1,8 +1,8 @@
 use slashy::{
     argument::Argument,
     command,
-    commands::CommandResult,
-    framework::CommandContext,
+    commands::{Command, CommandResult},
+    framework::{CommandContext, CommandInit},
     subcommand,
 };
 
@@ -30,3 +30,711 @@ fn command_macro_test() {
     assert_eq!(Some(&Argument::Integer(12)), args.0.get("test"));
     assert_eq!(None, args.0.get("testt"));
 }
+
+#[test]
+fn command_macro_alias_test() {
+    #[subcommand]
+    fn points(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        stats,
+        "get stats",
+        [
+            optional SubCommandGroup get | "get stats" aliases("g", "fetch") [
+                optional SubCommand points = points | "get points" aliases("pts")
+            ]
+        ]
+    }
+
+    let cmd: Command = STATS_COMMAND::command_init();
+    assert!(Argument::parse_message("stats fetch pts", &cmd.arguments_tree).is_some());
+    assert!(
+        Argument::parse_message_with_case("stats G Points", &cmd.arguments_tree, true).is_some()
+    );
+
+    let json = serde_json::to_string(&cmd).unwrap();
+    assert!(!json.contains("fetch"));
+    assert!(!json.contains("pts"));
+}
+
+#[test]
+fn command_macro_category_test() {
+    #[subcommand]
+    fn grid(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        grid,
+        "prints a grid",
+        grid,
+        [
+            required Integer size | "the size of the grid" {"small": 1, "medium": 5},
+            optional String fill | "the character to fill with"
+        ],
+        category("fun")
+    }
+
+    let cmd: Command = GRID_COMMAND::command_init();
+    assert_eq!(cmd.category, Some("fun"));
+    assert_eq!(cmd.usage_lines("!"), vec!["!grid <size: 1|5> [fill]"]);
+}
+
+#[test]
+fn command_usage_test() {
+    #[subcommand]
+    fn points(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    #[subcommand]
+    fn set(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        stats,
+        "get or set stats",
+        [
+            optional SubCommandGroup get | "get stats" aliases("g") [
+                optional SubCommand points = points | "get points" [
+                    required User user | "the user",
+                    optional Integer page | "the page" {"first": 1, "second": 2}
+                ]
+            ],
+            optional SubCommand set = set | "set stats"
+        ]
+    }
+
+    let cmd: Command = STATS_COMMAND::command_init();
+    assert_eq!(cmd.usage("!"), "!stats <get|set>");
+    assert_eq!(cmd.usage_for("!", &["get"], false), "!stats get <points>");
+    assert_eq!(
+        cmd.usage_for("!", &["G", "points", "12"], true),
+        "!stats get points <user> [page: 1|2]"
+    );
+    assert_eq!(cmd.usage_for("!", &["G"], false), "!stats <get|set>");
+    assert_eq!(cmd.usage_for("/", &["set", "extra"], false), "/stats set");
+    assert_eq!(cmd.usage_lines("!"), vec![
+        "!stats get points <user> [page: 1|2]",
+        "!stats set"
+    ]);
+}
+
+#[test]
+fn command_group_test() {
+    use slashy::{commands::CommandGroup, framework::GroupInit, permissions::ADMINISTRATOR};
+
+    #[subcommand]
+    fn kick(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        kick,
+        "kicks a user",
+        kick,
+        [
+            required User user | "the user to kick"
+        ],
+        group("moderation")
+    }
+
+    struct ModerationGroup;
+    impl GroupInit for ModerationGroup {
+        fn group_init() -> CommandGroup {
+            CommandGroup {
+                name: "moderation",
+                description: "commands for moderators",
+                checks: vec![ADMINISTRATOR],
+                works_in_dms: false,
+                commands: vec![KICK_COMMAND::command_init()],
+            }
+        }
+    }
+
+    let group = ModerationGroup::group_init();
+    assert_eq!(group.commands.len(), 1);
+    assert_eq!(group.commands[0].group, Some(group.name));
+    assert_eq!(group.commands[0].category, None);
+}
+
+#[tokio::test]
+async fn command_group_check_test() {
+    use serde_json::json;
+    use slashy::{
+        commands::CommandGroup,
+        permissions_check,
+        serenity::{
+            client::Context,
+            model::{
+                channel::{Channel, GuildChannel, PrivateChannel},
+                guild::Member,
+            },
+        },
+    };
+
+    #[permissions_check]
+    async fn allow(
+        _ctx: &Context,
+        _member: &Member,
+        _channel: &GuildChannel,
+    ) -> CommandResult<bool> {
+        Ok(true)
+    }
+
+    #[permissions_check]
+    async fn deny(
+        _ctx: &Context,
+        _member: &Member,
+        _channel: &GuildChannel,
+    ) -> CommandResult<bool> {
+        Ok(false)
+    }
+
+    let user = json!({"id": "2", "username": "user", "discriminator": "0001", "avatar": null});
+    let member: Member = serde_json::from_value(json!({
+        "guild_id": "1",
+        "user": user,
+        "roles": [],
+        "joined_at": null,
+        "deaf": false,
+        "mute": false,
+    }))
+    .unwrap();
+    let guild_channel = Channel::Guild(
+        serde_json::from_value::<GuildChannel>(json!({
+            "id": "3",
+            "guild_id": "1",
+            "type": 0,
+            "name": "general",
+            "position": 0,
+            "permission_overwrites": [],
+        }))
+        .unwrap(),
+    );
+    let dm = Channel::Private(
+        serde_json::from_value::<PrivateChannel>(json!({
+            "id": "4",
+            "type": 1,
+            "recipients": [user],
+        }))
+        .unwrap(),
+    );
+
+    let ctx = CommandContext::new_test(Default::default()).ctx;
+    let mut group = CommandGroup {
+        name: "moderation",
+        description: "commands for moderators",
+        checks: vec![allow, deny],
+        works_in_dms: false,
+        commands: Vec::new(),
+    };
+
+    let rejected = group.check_in(&ctx, &guild_channel, Some(&member)).await;
+    assert_eq!(
+        rejected.unwrap_err().to_string(),
+        "User does not have permissions"
+    );
+    assert!(group.check_in(&ctx, &guild_channel, None).await.is_err());
+
+    group.checks = vec![allow];
+    assert!(group
+        .check_in(&ctx, &guild_channel, Some(&member))
+        .await
+        .is_ok());
+
+    let dm_rejected = group.check_in(&ctx, &dm, None).await;
+    assert_eq!(
+        dm_rejected.unwrap_err().to_string(),
+        "Command is not available in dms"
+    );
+    group.works_in_dms = true;
+    assert!(group.check_in(&ctx, &dm, None).await.is_ok());
+}
+
+#[test]
+fn command_macro_cooldown_test() {
+    use slashy::cooldown::{BucketType, Cooldown};
+    use std::time::Duration;
+
+    #[subcommand]
+    fn render(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        render,
+        "renders a scene",
+        [
+            optional SubCommand scene = render | "render a scene" cooldown(1, 30, User)
+        ],
+        cooldown(10, 60, Guild)
+    }
+
+    let cmd: Command = RENDER_COMMAND::command_init();
+    assert_eq!(
+        cmd.cooldown,
+        Some(Cooldown {
+            uses: 10,
+            per: Duration::from_secs(60),
+            bucket: BucketType::Guild,
+        })
+    );
+    let scene = &cmd.arguments_tree.children.as_ref().unwrap()[0];
+    assert_eq!(
+        scene.cooldown(),
+        Some(Cooldown {
+            uses: 1,
+            per: Duration::from_secs(30),
+            bucket: BucketType::User,
+        })
+    );
+}
+
+#[test]
+fn command_macro_concurrency_test() {
+    use slashy::{
+        concurrency::{ConcurrencyMode, MaxConcurrency},
+        cooldown::BucketType,
+    };
+
+    #[subcommand]
+    fn render(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        render,
+        "renders a scene",
+        render,
+        [],
+        max_concurrency(2, Guild, Queue)
+    }
+
+    let cmd: Command = RENDER_COMMAND::command_init();
+    assert_eq!(
+        cmd.max_concurrency,
+        Some(MaxConcurrency {
+            limit: 2,
+            bucket: BucketType::Guild,
+            mode: ConcurrencyMode::Queue,
+        })
+    );
+}
+
+#[test]
+fn command_macro_scope_test() {
+    use slashy::{commands::CommandScope, serenity::model::id::GuildId};
+
+    #[subcommand]
+    fn beta(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        beta,
+        "a beta feature",
+        beta,
+        [],
+        scope(Guilds(1234, 5678))
+    }
+
+    let cmd: Command = BETA_COMMAND::command_init();
+    assert_eq!(
+        cmd.scope,
+        CommandScope::Guilds(vec![GuildId(1234), GuildId(5678)])
+    );
+}
+
+#[test]
+fn slashy_args_test() {
+    use slashy::{argument::SlashyArgs, serenity::model::id::UserId, SlashyArgs};
+
+    #[derive(SlashyArgs)]
+    struct BanArgs {
+        /// the user to ban
+        user: UserId,
+        #[arg(description = "how many days of messages to delete")]
+        days: i64,
+        /// why they were banned
+        reason: Option<String>,
+    }
+
+    #[subcommand]
+    fn ban(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        ban,
+        "bans a user",
+        ban,
+        args(BanArgs)
+    }
+
+    let cmd: Command = BAN_COMMAND::command_init();
+    let children = cmd.arguments_tree.children.as_ref().unwrap();
+    assert_eq!(children.iter().map(|c| c.name()).collect::<Vec<_>>(), vec![
+        "user", "days", "reason"
+    ]);
+    assert_eq!(
+        children[1].description(),
+        "how many days of messages to delete"
+    );
+
+    let args = Argument::parse_message("ban 1234 7", &cmd.arguments_tree).unwrap();
+    let ban_args = BanArgs::from_args(&args.0).unwrap();
+    assert_eq!(ban_args.user, UserId(1234));
+    assert_eq!(ban_args.days, 7);
+    assert_eq!(ban_args.reason, None);
+
+    let args = Argument::parse_message("ban 1234 7 spam", &cmd.arguments_tree).unwrap();
+    assert_eq!(
+        BanArgs::from_args(&args.0).unwrap().reason,
+        Some("spam".to_owned())
+    );
+
+    let mut args = args.0;
+    args.remove("days");
+    assert_eq!(
+        BanArgs::from_args(&args).err().unwrap().to_string(),
+        "The `days` argument is missing"
+    );
+}
+
+#[test]
+fn slashy_args_settings_test() {
+    use std::collections::HashMap;
+
+    use slashy::{argument::SlashyArgs, commands::CommandArguments, SlashyArgs};
+
+    #[derive(SlashyArgs)]
+    struct GridArgs {
+        #[arg(description = "the size of the grid", choices = {"small": 1, "large": 12})]
+        size: i64,
+        #[arg(description = "the gap between cells", min = -1, max = 4)]
+        gap: Option<i64>,
+    }
+
+    match &GridArgs::arguments()[0] {
+        CommandArguments::Integer {
+            choices: Some(choices),
+            ..
+        } => assert_eq!(
+            choices
+                .iter()
+                .map(|c| (c.name, c.value))
+                .collect::<Vec<_>>(),
+            vec![("small", 1), ("large", 12)]
+        ),
+        _ => panic!("Expected an Integer argument with choices"),
+    }
+
+    // The bounds are also sent to discord
+    let gap = serde_json::to_value(&GridArgs::arguments()[1]).unwrap();
+    assert_eq!(gap["min_value"], -1);
+    assert_eq!(gap["max_value"], 4);
+
+    let args = HashMap::from([
+        ("size".to_owned(), Argument::Integer(12)),
+        ("gap".to_owned(), Argument::Integer(-1)),
+    ]);
+    let grid_args = GridArgs::from_args(&args).unwrap();
+    assert_eq!(grid_args.size, 12);
+    assert_eq!(grid_args.gap, Some(-1));
+
+    let args = HashMap::from([
+        ("size".to_owned(), Argument::Integer(12)),
+        ("gap".to_owned(), Argument::Integer(5)),
+    ]);
+    assert_eq!(
+        GridArgs::from_args(&args).err().unwrap().to_string(),
+        "The `gap` argument has to be at most 4"
+    );
+}
+
+#[test]
+fn command_attribute_test() {
+    use slashy::{
+        attr::command,
+        serenity::{model::id::UserId, FutureExt},
+    };
+
+    /// bans a user
+    #[command(category("moderation"))]
+    async fn ban(
+        _ctx: &CommandContext,
+        #[arg(description = "the user to ban")] user: UserId,
+        #[arg(description = "why they were banned")] reason: Option<String>,
+    ) -> CommandResult {
+        assert_eq!(user, UserId(1234));
+        assert_eq!(reason.as_deref(), Some("spam"));
+        Ok(())
+    }
+
+    let cmd = BAN_COMMAND::command_init();
+    assert_eq!(cmd.name, "ban");
+    assert_eq!(cmd.description, "bans a user");
+    assert_eq!(cmd.category, Some("moderation"));
+    let children = cmd.arguments_tree.children.as_ref().unwrap();
+    assert_eq!(children.iter().map(|c| c.name()).collect::<Vec<_>>(), vec![
+        "user", "reason"
+    ]);
+    assert!(children[0].required());
+    assert!(!children[1].required());
+
+    let args = Argument::parse_message("ban 1234 spam", &cmd.arguments_tree)
+        .unwrap()
+        .0;
+    ban(&CommandContext::new_test(args))
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+
+    let result = ban(&CommandContext::new_test(Default::default()))
+        .now_or_never()
+        .unwrap();
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "The `user` argument is missing"
+    );
+
+    /// sets the type
+    #[command]
+    async fn kind(
+        _ctx: &CommandContext,
+        /// the type to set
+        r#type: String,
+    ) -> CommandResult {
+        assert_eq!(r#type, "big");
+        Ok(())
+    }
+
+    // Raw identifiers are registered and read without the `r#`
+    let cmd = KIND_COMMAND::command_init();
+    let children = cmd.arguments_tree.children.as_ref().unwrap();
+    assert_eq!(children[0].name(), "type");
+    let args = Argument::parse_message("kind big", &cmd.arguments_tree)
+        .unwrap()
+        .0;
+    kind(&CommandContext::new_test(args))
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+}
+
+#[test]
+fn slashy_choice_test() {
+    use std::collections::HashMap;
+
+    use slashy::{argument::SlashyArgs, commands::CommandArguments, SlashyArgs, SlashyChoice};
+
+    #[derive(SlashyChoice, Debug, PartialEq)]
+    enum Size {
+        Small,
+        Medium,
+        #[choice(name = "Extra large", value = "xl")]
+        ExtraLarge,
+    }
+
+    #[derive(SlashyArgs)]
+    struct GridArgs {
+        /// the size of the grid
+        size: Size,
+    }
+
+    match &GridArgs::arguments()[0] {
+        CommandArguments::String {
+            choices: Some(choices),
+            ..
+        } => assert_eq!(
+            choices
+                .iter()
+                .map(|c| (c.name, c.value.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Small", "small"),
+                ("Medium", "medium"),
+                ("Extra large", "xl")
+            ]
+        ),
+        _ => panic!("Expected a String argument with choices"),
+    }
+
+    let args = HashMap::from([("size".to_owned(), Argument::String("xl".to_owned()))]);
+    assert_eq!(GridArgs::from_args(&args).unwrap().size, Size::ExtraLarge);
+    assert_eq!(
+        CommandContext::new_test(args).get_choice_arg::<Size>("size"),
+        Some(Size::ExtraLarge)
+    );
+
+    let args = HashMap::from([("size".to_owned(), Argument::String("huge".to_owned()))]);
+    assert_eq!(
+        GridArgs::from_args(&args).err().unwrap().to_string(),
+        "The `size` argument has an invalid value"
+    );
+    assert_eq!(
+        CommandContext::new_test(args).get_choice_arg::<Size>("size"),
+        None
+    );
+}
+
+#[test]
+fn slashy_choice_integer_test() {
+    use std::collections::HashMap;
+
+    use slashy::{argument::SlashyArgs, commands::CommandArguments, SlashyArgs, SlashyChoice};
+
+    #[derive(SlashyChoice, Debug, PartialEq)]
+    enum Grid {
+        Small = 1,
+        #[choice(name = "medium", value = 5)]
+        Medium,
+        Negative = -2,
+    }
+
+    #[derive(SlashyArgs)]
+    struct GridArgs {
+        /// the size of the grid
+        size: Grid,
+    }
+
+    match &GridArgs::arguments()[0] {
+        CommandArguments::Integer {
+            choices: Some(choices),
+            ..
+        } => assert_eq!(
+            choices
+                .iter()
+                .map(|c| (c.name, c.value))
+                .collect::<Vec<_>>(),
+            vec![("Small", 1), ("medium", 5), ("Negative", -2)]
+        ),
+        _ => panic!("Expected an Integer argument with choices"),
+    }
+
+    let args = HashMap::from([("size".to_owned(), Argument::Integer(-2))]);
+    assert_eq!(GridArgs::from_args(&args).unwrap().size, Grid::Negative);
+    assert_eq!(
+        CommandContext::new_test(args).get_choice_arg::<Grid>("size"),
+        Some(Grid::Negative)
+    );
+
+    let args = HashMap::from([("size".to_owned(), Argument::String("small".to_owned()))]);
+    assert_eq!(
+        CommandContext::new_test(args).get_choice_arg::<Grid>("size"),
+        None
+    );
+}
+
+#[test]
+fn command_macro_doc_comment_test() {
+    /// ping pong
+    #[subcommand]
+    fn pong(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        ping,
+        pong,
+        [
+            /// text to echo,
+            /// repeated back
+            optional String text
+        ]
+    }
+
+    let cmd: Command = PING_COMMAND::command_init();
+    assert_eq!(cmd.description, "ping pong");
+    assert_eq!(
+        cmd.arguments_tree.children.as_ref().unwrap()[0].description(),
+        "text to echo, repeated back"
+    );
+}
+
+#[test]
+fn command_macro_imported_doc_comment_test() {
+    mod handlers {
+        use slashy::{commands::CommandResult, framework::CommandContext, subcommand};
+
+        /// ping pong
+        #[subcommand]
+        pub fn pong(_cmd: &CommandContext) -> CommandResult {
+            Ok(())
+        }
+    }
+
+    // The doc comment is stored next to the function and has to be imported with it
+    use handlers::{pong, __SLASHY_DESC_PONG};
+
+    command! {
+        ping,
+        pong,
+        []
+    }
+
+    let cmd: Command = PING_COMMAND::command_init();
+    assert_eq!(cmd.description, "ping pong");
+}
+
+#[test]
+fn command_macro_shared_name_test() {
+    // Functions can share their name with a module
+    #[allow(dead_code)]
+    mod echo {}
+
+    /// echoes text
+    #[subcommand]
+    fn echo(_cmd: &CommandContext) -> CommandResult {
+        Ok(())
+    }
+
+    command! {
+        echo,
+        echo,
+        []
+    }
+
+    let cmd: Command = ECHO_COMMAND::command_init();
+    assert_eq!(cmd.description, "echoes text");
+}
+
+#[test]
+fn command_macro_struct_test() {
+    mod commands {
+        use slashy::{command, commands::CommandResult, framework::CommandContext, subcommand};
+
+        #[subcommand]
+        async fn ping(_ctx: &CommandContext) -> CommandResult {
+            Ok(())
+        }
+
+        command! {
+            ping,
+            "ping pong",
+            ping,
+            [
+                optional String text | "text to echo"
+            ],
+            struct(pub(crate) Ping)
+        }
+
+        command! {
+            pong,
+            "pong ping",
+            ping,
+            [
+                optional String text | "text to echo" {"a": "a", "b": "b"}
+            ]
+        }
+    }
+
+    let ping = commands::Ping::command_init();
+    assert_eq!(ping.name, "ping");
+    let pong = commands::PONG_COMMAND::command_init();
+    assert_eq!(pong.name, "pong");
+}
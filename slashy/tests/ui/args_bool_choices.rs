@@ -0,0 +1,9 @@
+use slashy::SlashyArgs;
+
+#[derive(SlashyArgs)]
+struct EchoArgs {
+    #[arg(description = "whether to echo", choices = {"yes": true})]
+    echo: bool,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use slashy::SlashyArgs;
+
+#[derive(SlashyArgs)]
+struct PageArgs {
+    #[arg(description = "the page to show", max = 3000000000)]
+    page: i32,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use slashy::SlashyArgs;
+
+#[derive(SlashyArgs)]
+struct EchoArgs {
+    text: String,
+}
+
+fn main() {}
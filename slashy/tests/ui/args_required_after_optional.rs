@@ -0,0 +1,11 @@
+use slashy::SlashyArgs;
+
+#[derive(SlashyArgs)]
+struct AddArgs {
+    /// the first number
+    a: Option<i64>,
+    /// the second number
+    b: i64,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use slashy::command;
+
+command! {
+    /// ping pong
+    ping,
+    [
+        optional String text
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use slashy::command;
+
+command! {
+    toggle,
+    "toggles something",
+    [
+        required Boolean value | "the new value" {"on": true, "off": false}
+    ]
+}
+
+fn main() {}
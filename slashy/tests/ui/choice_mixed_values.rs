@@ -0,0 +1,9 @@
+use slashy::SlashyChoice;
+
+#[derive(SlashyChoice)]
+enum Size {
+    Small = 1,
+    Large,
+}
+
+fn main() {}
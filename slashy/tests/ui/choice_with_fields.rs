@@ -0,0 +1,9 @@
+use slashy::SlashyChoice;
+
+#[derive(SlashyChoice)]
+enum Size {
+    Small,
+    Custom(i64),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use slashy::{attr::command, commands::CommandResult, framework::CommandContext};
+
+#[command]
+async fn ping(ctx: &CommandContext) -> CommandResult {
+    ctx.send_str("pong").await?;
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use slashy::command;
+
+command! {
+    add,
+    "adds two numbers",
+    [
+        required Integer a | "the first number",
+        required Integer a | "the second number"
+    ]
+}
+
+fn main() {}
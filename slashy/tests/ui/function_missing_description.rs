@@ -0,0 +1,17 @@
+use slashy::{command, commands::CommandResult, framework::CommandContext, subcommand};
+
+#[subcommand]
+async fn pong(_ctx: &CommandContext) -> CommandResult {
+    Ok(())
+}
+
+command! {
+    ping,
+    pong,
+    [
+        /// text to echo
+        optional String text
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use slashy::command;
+
+command! {
+    ping,
+    "ping pong ping pong ping pong ping pong ping pong ping pong ping pong ping pong ping pong ping pong ping pong",
+    [
+        optional String text | "text to echo"
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use slashy::command;
+
+command! {
+    stats,
+    "get a user's stats",
+    [
+        optional SubCommand get | "get stats",
+        optional User user | "the user"
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use slashy::command;
+
+command! {
+    stats,
+    "get a user's stats",
+    [
+        optional SubCommand get | "get stats" [
+            optional SubCommandGroup points | "get points" [
+                optional SubCommand total | "get the total"
+            ]
+        ]
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use slashy::command;
+
+command! {
+    add,
+    "adds two numbers",
+    [
+        optional Integer a | "the first number",
+        required Integer b | "the second number"
+    ]
+}
+
+fn main() {}
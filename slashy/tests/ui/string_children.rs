@@ -0,0 +1,13 @@
+use slashy::command;
+
+command! {
+    echo,
+    "echoes text",
+    [
+        required String text | "the text" [
+            optional Integer times | "how many times"
+        ]
+    ]
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use slashy::command;
+
+command! {
+    Ping,
+    "ping pong",
+    [
+        optional String text | "text to echo"
+    ]
+}
+
+fn main() {}
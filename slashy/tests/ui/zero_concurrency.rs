@@ -0,0 +1,12 @@
+use slashy::command;
+
+command! {
+    render,
+    "renders an image",
+    [
+        optional String text | "text to render"
+    ],
+    max_concurrency(0, Guild, Queue)
+}
+
+fn main() {}
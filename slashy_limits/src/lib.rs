@@ -0,0 +1,17 @@
+//! Discord's limits for slash commands
+//!
+//! Shared by `slashy`, which checks commands against them at runtime, and `slashy_macros`, which checks them while compiling.
+#![warn(missing_docs)]
+
+/// The most commands discord allows to be registered at once, globally or in one guild
+pub const MAX_COMMANDS: usize = 100;
+/// The most options a command or option can have
+pub const MAX_OPTIONS: usize = 25;
+/// The most choices an option can have
+pub const MAX_CHOICES: usize = 25;
+/// The longest a name can be
+pub const MAX_NAME_LENGTH: usize = 32;
+/// The longest a description, choice name or choice value can be
+pub const MAX_DESCRIPTION_LENGTH: usize = 100;
+/// The most characters the names, descriptions and choices of a command can have combined
+pub const MAX_COMMAND_LENGTH: usize = 8000;
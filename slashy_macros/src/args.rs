@@ -0,0 +1,399 @@
+use proc_macro2::{Literal, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute,
+    Data,
+    DeriveInput,
+    Error,
+    Fields,
+    GenericArgument,
+    Ident,
+    Lit,
+    LitInt,
+    LitStr,
+    PathArguments,
+    Token,
+    Type,
+};
+
+use crate::command::{check_description, check_name, doc_comment, ArgumentOption};
+
+/// Generates the SlashyArgs impl for a struct with named fields
+pub fn derive_slashy_args(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ =>
+                return Err(Error::new(
+                    input.span(),
+                    "SlashyArgs can only be derived for structs with named fields",
+                )),
+        },
+        _ =>
+            return Err(Error::new(
+                input.span(),
+                "SlashyArgs can only be derived for structs",
+            )),
+    };
+
+    let mut arguments = Vec::new();
+    let mut readers = Vec::new();
+    let mut optional = false;
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let argument = TypedArgument::new(ident, &field.ty, &field.attrs, &mut optional)?;
+        arguments.push(argument.command_argument());
+        let arg_name = &argument.name;
+        let read = argument.read(quote! {args.get(#arg_name)});
+        readers.push(quote! {#ident: #read});
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::slashy::argument::SlashyArgs for #name #ty_generics #where_clause {
+            fn arguments() -> ::std::vec::Vec<::slashy::commands::CommandArguments> {
+                vec![#(#arguments),*]
+            }
+
+            fn from_args(
+                args: &::std::collections::HashMap<::std::string::String, ::slashy::argument::Argument>,
+            ) -> ::std::result::Result<Self, ::slashy::commands::SlashyError> {
+                Ok(#name {
+                    #(#readers),*
+                })
+            }
+        }
+    })
+}
+
+/// An argument read from a struct field or function parameter
+pub(crate) struct TypedArgument<'a> {
+    pub(crate) name: String,
+    description: LitStr,
+    ty: &'a Type,
+    required: bool,
+    choices: Option<ArgumentOption>,
+    min: Option<i64>,
+    max: Option<i64>,
+}
+
+impl<'a> TypedArgument<'a> {
+    /// Reads the argument's settings, `optional` tracks whether an earlier argument was optional
+    pub(crate) fn new(
+        ident: &Ident,
+        ty: &'a Type,
+        attrs: &[Attribute],
+        optional: &mut bool,
+    ) -> syn::Result<Self> {
+        check_name(ident)?;
+        let settings = ArgSettings::new(attrs)?;
+        let description = match settings
+            .description
+            .or_else(|| doc_comment(attrs, ident.span()))
+        {
+            Some(description) => description,
+            None =>
+                return Err(Error::new(
+                    ident.span(),
+                    "Arguments need a description, add a doc comment or `#[arg(description = \
+                     \"...\")]`",
+                )),
+        };
+        check_description(&description)?;
+
+        let (ty, required) = match option_inner(ty) {
+            Some(inner) => (inner, false),
+            None => (ty, true),
+        };
+        if required && *optional {
+            return Err(Error::new(
+                ident.span(),
+                "Cannot have required argument after optional argument",
+            ));
+        }
+        *optional |= !required;
+
+        let kind = argument_kind(ty);
+        if let Some(choices) = &settings.choices {
+            choices.check()?;
+            let valid = match kind {
+                Some(ArgKind::String) => choices
+                    .options
+                    .iter()
+                    .all(|c| matches!(c.value, Lit::Str(_))),
+                Some(ArgKind::Integer) => choices
+                    .options
+                    .iter()
+                    .all(|c| matches!(c.value, Lit::Int(_))),
+                None =>
+                    return Err(Error::new(
+                        ty.span(),
+                        "Only String and integer arguments can have choices",
+                    )),
+            };
+            if !valid {
+                return Err(Error::new(
+                    ty.span(),
+                    "The values of the choices have to match the argument's type",
+                ));
+            }
+        }
+        if (settings.min.is_some() || settings.max.is_some()) && kind != Some(ArgKind::Integer) {
+            return Err(Error::new(
+                ty.span(),
+                "Only integer arguments can have a `min` or `max`",
+            ));
+        }
+        if let (Some(min), Some(max)) = (settings.min, settings.max) {
+            if min > max {
+                return Err(Error::new(ident.span(), "`min` can't be more than `max`"));
+            }
+        }
+        if is_i32(ty)
+            && [settings.min, settings.max]
+                .iter()
+                .flatten()
+                .any(|b| i32::try_from(*b).is_err())
+        {
+            return Err(Error::new(
+                ty.span(),
+                "`min` and `max` have to fit in an i32",
+            ));
+        }
+
+        Ok(TypedArgument {
+            name: ident.unraw().to_string(),
+            description,
+            ty,
+            required,
+            choices: settings.choices,
+            min: settings.min,
+            max: settings.max,
+        })
+    }
+
+    /// The CommandArguments for the argument
+    pub(crate) fn command_argument(&self) -> TokenStream {
+        let TypedArgument {
+            name,
+            description,
+            ty,
+            required,
+            choices,
+            min,
+            max,
+        } = self;
+        let choices = match choices {
+            Some(choices) => quote! {Some(#choices)},
+            None => quote! {None},
+        };
+        match argument_kind(ty) {
+            Some(ArgKind::Integer) if self.choices.is_some() || min.is_some() || max.is_some() => {
+                let min = bound(*min);
+                let max = bound(*max);
+                quote! {
+                    ::slashy::commands::CommandArguments::Integer {
+                        name: #name,
+                        description: #description,
+                        required: #required,
+                        choices: #choices,
+                        min_value: #min,
+                        max_value: #max,
+                    }
+                }
+            }
+            Some(ArgKind::String) if self.choices.is_some() => quote! {
+                ::slashy::commands::CommandArguments::String {
+                    name: #name,
+                    description: #description,
+                    required: #required,
+                    choices: #choices,
+                }
+            },
+            _ => quote_spanned! {ty.span()=>
+                <#ty as ::slashy::argument::FromArgument>::command_argument(#name, #description, #required)
+            },
+        }
+    }
+
+    /// Reads the value from `lookup`, an `Option<&Argument>`
+    ///
+    /// Returns early with an error if a required argument is missing, the argument has an invalid value or is outside of its `min` and `max`
+    pub(crate) fn read(&self, lookup: TokenStream) -> TokenStream {
+        let ty = self.ty;
+        let invalid = format!("The `{}` argument has an invalid value", self.name);
+        let mut checks = Vec::new();
+        if let Some(min) = self.min {
+            let message = format!("The `{}` argument has to be at least {min}", self.name);
+            let min = Literal::i64_unsuffixed(min);
+            checks.push(quote! {
+                if value < #min {
+                    return Err(::slashy::commands::SlashyError::new(#message).into());
+                }
+            });
+        }
+        if let Some(max) = self.max {
+            let message = format!("The `{}` argument has to be at most {max}", self.name);
+            let max = Literal::i64_unsuffixed(max);
+            checks.push(quote! {
+                if value > #max {
+                    return Err(::slashy::commands::SlashyError::new(#message).into());
+                }
+            });
+        }
+        let read = quote_spanned! {ty.span()=>
+            <#ty as ::slashy::argument::FromArgument>::from_argument(arg)
+                .ok_or_else(|| ::slashy::commands::SlashyError::new(#invalid))?
+        };
+        let read = if checks.is_empty() {
+            read
+        } else {
+            quote! {{
+                let value = #read;
+                #(#checks)*
+                value
+            }}
+        };
+
+        if self.required {
+            let missing = format!("The `{}` argument is missing", self.name);
+            quote! {
+                match #lookup {
+                    Some(arg) => #read,
+                    None => return Err(::slashy::commands::SlashyError::new(#missing).into()),
+                }
+            }
+        } else {
+            quote! {
+                match #lookup {
+                    Some(arg) => Some(#read),
+                    None => None,
+                }
+            }
+        }
+    }
+}
+
+/// The settings given with `#[arg(...)]`
+#[derive(Default)]
+struct ArgSettings {
+    description: Option<LitStr>,
+    choices: Option<ArgumentOption>,
+    min: Option<i64>,
+    max: Option<i64>,
+}
+
+impl ArgSettings {
+    fn new(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut settings = ArgSettings::default();
+        for attr in attrs.iter().filter(|a| a.path.is_ident("arg")) {
+            let list =
+                attr.parse_args_with(Punctuated::<ArgSetting, Token![,]>::parse_terminated)?;
+            for setting in list {
+                match setting {
+                    ArgSetting::Description(d) => settings.description = Some(d),
+                    ArgSetting::Choices(c) => settings.choices = Some(c),
+                    ArgSetting::Min(m) => settings.min = Some(m),
+                    ArgSetting::Max(m) => settings.max = Some(m),
+                }
+            }
+        }
+        Ok(settings)
+    }
+}
+
+enum ArgSetting {
+    Description(LitStr),
+    Choices(ArgumentOption),
+    Min(i64),
+    Max(i64),
+}
+
+impl Parse for ArgSetting {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let ident = input.parse::<Ident>()?;
+        input.parse::<Token![=]>()?;
+        match ident.to_string().as_str() {
+            "description" => Ok(ArgSetting::Description(input.parse()?)),
+            "choices" => Ok(ArgSetting::Choices(input.parse()?)),
+            "min" => Ok(ArgSetting::Min(parse_int(input)?)),
+            "max" => Ok(ArgSetting::Max(parse_int(input)?)),
+            _ => Err(Error::new(
+                ident.span(),
+                "Unknown argument setting, expected `description`, `choices`, `min` or `max`",
+            )),
+        }
+    }
+}
+
+/// Parses an integer that can be negative
+fn parse_int(input: ParseStream) -> syn::Result<i64> {
+    let negative = input.parse::<Option<Token![-]>>()?.is_some();
+    let value = input.parse::<LitInt>()?.base10_parse::<i64>()?;
+    Ok(if negative { -value } else { value })
+}
+
+#[derive(PartialEq, Eq)]
+enum ArgKind {
+    String,
+    Integer,
+}
+
+/// Whether the type takes String or Integer choices
+fn argument_kind(ty: &Type) -> Option<ArgKind> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident == "String" {
+        Some(ArgKind::String)
+    } else if segment.ident == "i32" || segment.ident == "i64" {
+        Some(ArgKind::Integer)
+    } else {
+        None
+    }
+}
+
+/// Whether the type is `i32`
+fn is_i32(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) if path.qself.is_none() =>
+            path.path.segments.last().is_some_and(|s| s.ident == "i32"),
+        _ => false,
+    }
+}
+
+/// The tokens for an optional `min` or `max`
+fn bound(value: Option<i64>) -> TokenStream {
+    match value {
+        Some(value) => {
+            let value = Literal::i64_unsuffixed(value);
+            quote! {Some(#value)}
+        }
+        None => quote! {None},
+    }
+}
+
+/// Gets `T` if the type is `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let segment = match ty {
+        Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Option" {
+        return None;
+    }
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first()? {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
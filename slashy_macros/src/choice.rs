@@ -0,0 +1,275 @@
+use std::fmt::Display;
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, ToTokens};
+use slashy_limits::MAX_CHOICES;
+use syn::{
+    spanned::Spanned,
+    Data,
+    DeriveInput,
+    Error,
+    Expr,
+    ExprLit,
+    ExprUnary,
+    Fields,
+    Lit,
+    LitStr,
+    Meta,
+    NestedMeta,
+    UnOp,
+};
+
+use crate::command::check_length;
+
+/// Generates the SlashyChoice and FromArgument impls for a fieldless enum
+pub fn derive_slashy_choice(input: DeriveInput) -> syn::Result<TokenStream> {
+    let name = &input.ident;
+    let variants = match &input.data {
+        Data::Enum(data) => &data.variants,
+        _ =>
+            return Err(Error::new(
+                input.span(),
+                "SlashyChoice can only be derived for enums",
+            )),
+    };
+    if variants.is_empty() {
+        return Err(Error::new(input.span(), "Expected at least one variant"));
+    }
+    if let Some(extra) = variants.iter().nth(MAX_CHOICES) {
+        return Err(Error::new(
+            extra.span(),
+            format!("Cannot have more than {MAX_CHOICES} choices"),
+        ));
+    }
+
+    let mut choice_names = Vec::new();
+    let mut values = Vec::new();
+    let mut idents = Vec::new();
+    for variant in variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new(
+                variant.fields.span(),
+                "Choices can't have fields",
+            ));
+        }
+
+        let mut choice_name = LitStr::new(&variant.ident.to_string(), variant.ident.span());
+        let mut value = match &variant.discriminant {
+            Some((_, expr)) => ChoiceValue::Integer(discriminant(expr)?, expr.span()),
+            None => ChoiceValue::String(LitStr::new(
+                &snake_case(&variant.ident.to_string()),
+                variant.ident.span(),
+            )),
+        };
+        let mut explicit = variant.discriminant.is_some();
+        for attr in variant.attrs.iter().filter(|a| a.path.is_ident("choice")) {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta =>
+                    return Err(Error::new(
+                        meta.span(),
+                        "Expected `choice(name = \"...\", value = \"...\")`",
+                    )),
+            };
+            for nested in list.nested {
+                match nested {
+                    NestedMeta::Meta(Meta::NameValue(nv)) => match (&nv.lit, nv.path.get_ident()) {
+                        (Lit::Str(lit), Some(key)) if key == "name" => choice_name = lit.clone(),
+                        (Lit::Str(lit), Some(key)) if key == "value" => {
+                            value = ChoiceValue::String(lit.clone());
+                            explicit = true;
+                        }
+                        (Lit::Int(lit), Some(key)) if key == "value" => {
+                            value = ChoiceValue::Integer(lit.base10_parse()?, lit.span());
+                            explicit = true;
+                        }
+                        (Lit::Str(_) | Lit::Int(_), _) =>
+                            return Err(Error::new(
+                                nv.path.span(),
+                                "Unknown choice setting, expected `name` or `value`",
+                            )),
+                        (lit, _) =>
+                            return Err(Error::new(lit.span(), "Expected a string or an integer")),
+                    },
+                    nested =>
+                        return Err(Error::new(
+                            nested.span(),
+                            "Unknown choice setting, expected `name` or `value`",
+                        )),
+                }
+            }
+        }
+        check_length(&choice_name, "Choice names")?;
+        if let ChoiceValue::String(value) = &value {
+            check_length(value, "Choice values")?;
+        }
+
+        if let Some(first) = values.first() {
+            if std::mem::discriminant(first) != std::mem::discriminant(&value) {
+                let message = if explicit {
+                    "All choices have to be strings or all integers"
+                } else {
+                    "Integer choices need a value, add `= 1` or `#[choice(value = 1)]`"
+                };
+                return Err(Error::new(value.span(), message));
+            }
+        }
+        if values.contains(&value) {
+            return Err(Error::new(
+                value.span(),
+                format!("Duplicate choice value `{value}`"),
+            ));
+        }
+
+        choice_names.push(choice_name);
+        values.push(value);
+        idents.push(&variant.ident);
+    }
+
+    let (value_ty, variant, arg_type, bounds) = match values[0] {
+        ChoiceValue::String(_) => (quote! {str}, quote! {String}, quote! {String}, quote! {}),
+        ChoiceValue::Integer(..) => (
+            quote! {i32},
+            quote! {Integer},
+            quote! {Integer},
+            quote! {min_value: None, max_value: None,},
+        ),
+    };
+    let owned_values = values.iter().map(|v| match v {
+        ChoiceValue::String(lit) => quote! {#lit.to_owned()},
+        v => quote! {#v},
+    });
+    let patterns = values.iter().map(|v| match v {
+        ChoiceValue::String(_) => quote! {#v},
+        // Integers are compared by value so they're matched against a dereferenced value
+        v => quote! {&#v},
+    });
+    let references = values.iter().map(|v| match v {
+        ChoiceValue::String(_) => quote! {#v},
+        v => quote! {&#v},
+    });
+
+    Ok(quote! {
+        impl ::slashy::argument::SlashyChoice for #name {
+            type Value = #value_ty;
+
+            fn choices() -> ::std::vec::Vec<::slashy::commands::ArgumentChoice<<#value_ty as ::std::borrow::ToOwned>::Owned>> {
+                vec![#(::slashy::commands::ArgumentChoice {
+                    name: #choice_names,
+                    value: #owned_values,
+                }),*]
+            }
+
+            fn from_value(value: &#value_ty) -> ::std::option::Option<Self> {
+                match value {
+                    #(#patterns => Some(#name::#idents),)*
+                    _ => None,
+                }
+            }
+
+            fn value(&self) -> &'static #value_ty {
+                match self {
+                    #(#name::#idents => #references),*
+                }
+            }
+        }
+
+        impl ::slashy::argument::FromArgument for #name {
+            fn command_argument(
+                name: &'static str,
+                description: &'static str,
+                required: bool,
+            ) -> ::slashy::commands::CommandArguments {
+                ::slashy::commands::CommandArguments::#variant {
+                    name,
+                    description,
+                    required,
+                    choices: Some(<Self as ::slashy::argument::SlashyChoice>::choices()),
+                    #bounds
+                }
+            }
+
+            fn from_argument(arg: &::slashy::argument::Argument) -> ::std::option::Option<Self> {
+                match arg {
+                    ::slashy::argument::Argument::#arg_type(value) =>
+                        <Self as ::slashy::argument::SlashyChoice>::from_value(value),
+                    _ => None,
+                }
+            }
+        }
+    })
+}
+
+/// The value of a choice, every choice of an enum has to be the same type
+enum ChoiceValue {
+    String(LitStr),
+    Integer(i32, Span),
+}
+
+impl PartialEq for ChoiceValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ChoiceValue::String(a), ChoiceValue::String(b)) => a.value() == b.value(),
+            (ChoiceValue::Integer(a, _), ChoiceValue::Integer(b, _)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl ChoiceValue {
+    fn span(&self) -> Span {
+        match self {
+            ChoiceValue::String(lit) => lit.span(),
+            ChoiceValue::Integer(_, span) => *span,
+        }
+    }
+}
+
+impl Display for ChoiceValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChoiceValue::String(lit) => write!(f, "{}", lit.value()),
+            ChoiceValue::Integer(value, _) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl ToTokens for ChoiceValue {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            ChoiceValue::String(lit) => lit.to_tokens(tokens),
+            ChoiceValue::Integer(value, span) => {
+                let mut lit = Literal::i32_unsuffixed(*value);
+                lit.set_span(*span);
+                lit.to_tokens(tokens)
+            }
+        }
+    }
+}
+
+/// Reads an explicit discriminant like `Small = 1` as the choice's value
+fn discriminant(expr: &Expr) -> syn::Result<i32> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) => lit.base10_parse(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => Ok(-discriminant(expr)?),
+        expr => Err(Error::new(expr.span(), "Expected an integer")),
+    }
+}
+
+/// Converts a variant name like `ExtraLarge` to `extra_large`
+fn snake_case(name: &str) -> String {
+    let mut output = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() && i != 0 {
+            output.push('_');
+        }
+        output.extend(c.to_lowercase());
+    }
+    output
+}
@@ -1,18 +1,35 @@
-use proc_macro2::Literal;
 use quote::{format_ident, quote, quote_spanned, ToTokens, TokenStreamExt};
+use slashy_limits::{
+    MAX_CHOICES,
+    MAX_COMMAND_LENGTH,
+    MAX_DESCRIPTION_LENGTH,
+    MAX_NAME_LENGTH,
+    MAX_OPTIONS,
+};
 use syn::{
     braced,
     bracketed,
     ext::IdentExt,
+    parenthesized,
     parse::Parse,
     punctuated::Punctuated,
     spanned::Spanned,
-    token::{Brace, Bracket, Comma},
+    token::{Brace, Bracket, Comma, Paren},
+    Attribute,
     Error,
     Ident,
+    Lit,
+    LitInt,
+    LitStr,
+    Meta,
+    MetaNameValue,
     Token,
+    Type,
+    Visibility,
 };
 
+use crate::subcommand::description_const;
+
 
 mod kw {
     use syn::custom_keyword;
@@ -27,23 +44,43 @@ mod kw {
     custom_keyword!(User);
     custom_keyword!(Channel);
     custom_keyword!(Role);
+    custom_keyword!(aliases);
+    custom_keyword!(category);
+    custom_keyword!(group);
+    custom_keyword!(cooldown);
+    custom_keyword!(max_concurrency);
+    custom_keyword!(scope);
+    custom_keyword!(args);
 }
 
 pub struct CommandInput {
     name: Ident,
-    description: Literal,
+    /// `None` if the description is taken from the function's doc comment
+    description: Option<LitStr>,
     tree: Option<Punctuated<Argument, Token![,]>>,
     func: Option<Ident>,
+    modifiers: Vec<CommandModifier>,
 }
 
 impl Parse for CommandInput {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
         let name = input.call(Ident::parse_any)?;
-        input.parse::<Token![,]>()?;
-        let description = input.parse()?;
+        check_name(&name)?;
         input.parse::<Token![,]>()?;
 
-        let func = if input.peek(Bracket) {
+        let description = if input.peek(LitStr) {
+            let description = input.parse()?;
+            input.parse::<Token![,]>()?;
+            Some(description)
+        } else {
+            doc_comment(&attrs, name.span())
+        };
+        if let Some(description) = &description {
+            check_description(description)?;
+        }
+
+        let func = if input.peek(Bracket) || input.peek2(Paren) {
             None
         } else {
             let a = Some(input.parse()?);
@@ -58,41 +95,89 @@ impl Parse for CommandInput {
             bracketed!(content in input);
 
             let raw_children = content.parse_terminated(Argument::parse)?;
-
-            let mut optional = false;
-            for child in &raw_children {
-                match child.required {
-                    Required::Required(r) =>
-                        if optional {
-                            return Err(Error::new(
-                                r.span(),
-                                "Cannot have required argument after optional argument",
-                            ));
-                        },
-                    Required::Optional(_) => {
-                        optional = true;
-                    }
-                }
-            }
+            check_children(&raw_children, None)?;
 
             Some(raw_children)
         } else {
             None
         };
 
+        if tree.is_some() && input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        }
+
+        let mut modifiers = Vec::new();
+        while !input.is_empty() {
+            let modifier = input.parse::<CommandModifier>()?;
+            if let (CommandModifier::Args(ty), Some(_)) = (&modifier, &tree) {
+                return Err(Error::new(
+                    ty.span(),
+                    "Cannot use both an argument list and `args`",
+                ));
+            }
+            modifiers.push(modifier);
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        if description.is_none() && func.is_none() {
+            return Err(Error::new(
+                name.span(),
+                "Commands need a description, add a doc comment or a description after the name",
+            ));
+        }
+
+        let length = name.unraw().to_string().chars().count()
+            + description
+                .as_ref()
+                .map_or(0, |d| d.value().chars().count())
+            + tree.iter().flatten().map(Argument::length).sum::<usize>();
+        if length > MAX_COMMAND_LENGTH {
+            return Err(Error::new(
+                name.span(),
+                format!(
+                    "The names, descriptions and choices of a command can't be more than \
+                     {MAX_COMMAND_LENGTH} characters combined, found {length}"
+                ),
+            ));
+        }
+
         Ok(CommandInput {
             name,
             description,
             tree,
             func,
+            modifiers,
         })
     }
 }
 
 impl ToTokens for CommandInput {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        let name = &self.name;
-        let description = &self.description;
+        let description = match (&self.description, &self.func) {
+            (Some(d), _) => d.to_token_stream(),
+            (None, Some(f)) => {
+                // Checked here as the function's doc comment can only be read by #[subcommand]
+                let description = description_const(f);
+                tokens.append_all(quote_spanned! {f.span()=>
+                    const _: () = {
+                        let length = ::slashy::validation::description_length(#description);
+                        assert!(
+                            length != 0,
+                            "Commands need a description, add a doc comment to the command or its function"
+                        );
+                        assert!(
+                            length <= ::slashy::validation::MAX_DESCRIPTION_LENGTH,
+                            "Descriptions can't be more than 100 characters long"
+                        );
+                    };
+                });
+                quote! {#description}
+            }
+            (None, None) => unreachable!("Checked while parsing"),
+        };
         let func = match &self.func {
             Some(f) => quote! {Some(#f)},
             None => quote! {None},
@@ -104,35 +189,273 @@ impl ToTokens for CommandInput {
             }
             None => quote! {None},
         };
-        let struct_name = format_ident!("{}_COMMAND", name.to_string().to_uppercase());
-        let cmd_name = format!("{name}");
-        tokens.append_all(quote! {
-            use ::slashy::commands::{Command, CommandArguments, ArgumentChoice, CommandArgumentsTree};
-            use ::slashy::framework::CommandInit;
-            use std::iter::FromIterator;
-            pub struct #struct_name;
-            impl CommandInit for #struct_name {
-                fn command_init() -> Command {
-                    Command {
-                        name: #cmd_name,
-                        description: #description,
-                        arguments_tree: CommandArgumentsTree {
-                            children: #tree,
-                            func: #func
-                        }
-                    }
+        tokens.append_all(command_init(
+            &self.name,
+            &description,
+            tree,
+            func,
+            &self.modifiers,
+        ));
+    }
+}
+
+/// Generates the `{NAME}_COMMAND` struct and its CommandInit impl
+pub(crate) fn command_init(
+    name: &Ident,
+    description: &impl ToTokens,
+    mut tree: proc_macro2::TokenStream,
+    func: proc_macro2::TokenStream,
+    modifiers: &[CommandModifier],
+) -> proc_macro2::TokenStream {
+    let mut category = quote! {None};
+    let mut group = quote! {None};
+    let mut cooldown = quote! {None};
+    let mut max_concurrency = quote! {None};
+    let mut scope = quote! {::slashy::commands::CommandScope::Global};
+    let mut vis = quote! {pub};
+    let mut struct_name = format_ident!("{}_COMMAND", name.to_string().to_uppercase());
+    for modifier in modifiers {
+        match modifier {
+            CommandModifier::Category(c) => category = quote! {Some(#c)},
+            CommandModifier::Group(g) => group = quote! {Some(#g)},
+            CommandModifier::Cooldown(c) => cooldown = quote! {Some(#c)},
+            CommandModifier::MaxConcurrency(m) => max_concurrency = quote! {Some(#m)},
+            CommandModifier::Scope(s) => scope = quote! {#s},
+            CommandModifier::Args(ty) =>
+                tree = quote! {Some(<#ty as ::slashy::argument::SlashyArgs>::arguments())},
+            CommandModifier::Struct(v, n) => {
+                vis = v.to_token_stream();
+                struct_name = n.clone();
+            }
+        }
+    }
+    let cmd_name = format!("{name}");
+    let struct_doc = format!("Initializes the `{name}` command");
+    quote! {
+        #[doc = #struct_doc]
+        #vis struct #struct_name;
+        impl ::slashy::framework::CommandInit for #struct_name {
+            fn command_init() -> ::slashy::commands::Command {
+                ::slashy::commands::Command {
+                    name: #cmd_name,
+                    description: #description,
+                    arguments_tree: ::slashy::commands::CommandArgumentsTree {
+                        children: #tree,
+                        func: #func
+                    },
+                    category: #category,
+                    group: #group,
+                    cooldown: #cooldown,
+                    max_concurrency: #max_concurrency,
+                    scope: #scope,
                 }
             }
+        }
+    }
+}
+
+/// Extra settings given after the argument tree of a command
+pub(crate) enum CommandModifier {
+    Category(LitStr),
+    Group(LitStr),
+    Cooldown(CooldownInput),
+    MaxConcurrency(MaxConcurrencyInput),
+    Scope(ScopeInput),
+    Args(Box<Type>),
+    Struct(Visibility, Ident),
+}
+
+impl Parse for CommandModifier {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let lookahead = input.lookahead1();
+        let content;
+
+        if lookahead.peek(kw::category) {
+            input.parse::<kw::category>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Category(content.parse()?))
+        } else if lookahead.peek(kw::group) {
+            input.parse::<kw::group>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Group(content.parse()?))
+        } else if lookahead.peek(kw::cooldown) {
+            input.parse::<kw::cooldown>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Cooldown(content.parse()?))
+        } else if lookahead.peek(kw::max_concurrency) {
+            input.parse::<kw::max_concurrency>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::MaxConcurrency(content.parse()?))
+        } else if lookahead.peek(kw::scope) {
+            input.parse::<kw::scope>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Scope(content.parse()?))
+        } else if lookahead.peek(kw::args) {
+            input.parse::<kw::args>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Args(content.parse()?))
+        } else if lookahead.peek(Token![struct]) {
+            input.parse::<Token![struct]>()?;
+            parenthesized!(content in input);
+            Ok(CommandModifier::Struct(content.parse()?, content.parse()?))
+        } else {
+            Err(lookahead.error())
+        }
+    }
+}
+
+/// `uses, seconds, Bucket`
+pub(crate) struct CooldownInput {
+    uses: LitInt,
+    seconds: LitInt,
+    bucket: Ident,
+}
+
+impl Parse for CooldownInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let uses = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let seconds = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let bucket = parse_bucket(input)?;
+
+        Ok(CooldownInput {
+            uses,
+            seconds,
+            bucket,
+        })
+    }
+}
+
+fn parse_bucket(input: syn::parse::ParseStream) -> syn::Result<Ident> {
+    let bucket = input.parse::<Ident>()?;
+
+    if !["User", "Channel", "Guild", "Global"].contains(&bucket.to_string().as_str()) {
+        return Err(Error::new(
+            bucket.span(),
+            "Expected one of `User`, `Channel`, `Guild` or `Global`",
+        ));
+    }
+
+    Ok(bucket)
+}
+
+/// `Global`, `Dynamic` or `Guilds(id, id)`
+pub(crate) enum ScopeInput {
+    Global,
+    Guilds(Vec<LitInt>),
+    Dynamic,
+}
+
+impl Parse for ScopeInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let kind = input.parse::<Ident>()?;
+
+        match kind.to_string().as_str() {
+            "Global" => Ok(ScopeInput::Global),
+            "Dynamic" => Ok(ScopeInput::Dynamic),
+            "Guilds" => {
+                let content;
+                parenthesized!(content in input);
+                let guilds = content.parse_terminated::<LitInt, Token![,]>(LitInt::parse)?;
+                Ok(ScopeInput::Guilds(guilds.into_iter().collect()))
+            }
+            _ => Err(Error::new(
+                kind.span(),
+                "Expected one of `Global`, `Guilds` or `Dynamic`",
+            )),
+        }
+    }
+}
+
+impl ToTokens for ScopeInput {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.append_all(match self {
+            ScopeInput::Global => quote! {::slashy::commands::CommandScope::Global},
+            ScopeInput::Dynamic => quote! {::slashy::commands::CommandScope::Dynamic},
+            ScopeInput::Guilds(guilds) => quote! {
+                ::slashy::commands::CommandScope::Guilds(vec![
+                    #(::slashy::serenity::model::id::GuildId(#guilds)),*
+                ])
+            },
         });
     }
 }
 
+/// `limit, Bucket, Mode`
+pub(crate) struct MaxConcurrencyInput {
+    limit: LitInt,
+    bucket: Ident,
+    mode: Ident,
+}
+
+impl Parse for MaxConcurrencyInput {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let limit = input.parse::<LitInt>()?;
+        if limit.base10_parse::<u32>()? < 1 {
+            return Err(Error::new(
+                limit.span(),
+                "The concurrency limit has to be at least 1",
+            ));
+        }
+        input.parse::<Token![,]>()?;
+        let bucket = parse_bucket(input)?;
+        input.parse::<Token![,]>()?;
+        let mode = input.parse::<Ident>()?;
+
+        if !["Reject", "Queue"].contains(&mode.to_string().as_str()) {
+            return Err(Error::new(
+                mode.span(),
+                "Expected one of `Reject` or `Queue`",
+            ));
+        }
+
+        Ok(MaxConcurrencyInput {
+            limit,
+            bucket,
+            mode,
+        })
+    }
+}
+
+impl ToTokens for MaxConcurrencyInput {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let limit = &self.limit;
+        let bucket = &self.bucket;
+        let mode = &self.mode;
+        tokens.append_all(quote! {
+            ::slashy::concurrency::MaxConcurrency {
+                limit: #limit,
+                bucket: ::slashy::cooldown::BucketType::#bucket,
+                mode: ::slashy::concurrency::ConcurrencyMode::#mode,
+            }
+        })
+    }
+}
+
+impl ToTokens for CooldownInput {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let uses = &self.uses;
+        let seconds = &self.seconds;
+        let bucket = &self.bucket;
+        tokens.append_all(quote! {
+            ::slashy::cooldown::Cooldown {
+                uses: #uses,
+                per: ::std::time::Duration::from_secs(#seconds),
+                bucket: ::slashy::cooldown::BucketType::#bucket,
+            }
+        })
+    }
+}
+
 struct Argument {
     required: Required,
     ty: ArgType,
     name: Ident,
     func: Option<Ident>,
-    description: Literal,
+    description: LitStr,
+    aliases: Option<Punctuated<LitStr, Comma>>,
+    cooldown: Option<CooldownInput>,
     options: Option<ArgumentOption>,
     children: Option<Punctuated<Argument, Comma>>,
 }
@@ -160,6 +483,19 @@ impl ToTokens for Argument {
             None => quote! {None},
         };
 
+        let aliases = match &self.aliases {
+            Some(a) => {
+                let a = a.iter();
+                quote! {Some(vec![#(#a),*])}
+            }
+            None => quote! {None},
+        };
+
+        let cooldown = match &self.cooldown {
+            Some(c) => quote! {Some(#c)},
+            None => quote! {None},
+        };
+
         let span = self.name.span();
 
         tokens.append_all(match ty {
@@ -169,10 +505,12 @@ impl ToTokens for Argument {
                     description: #description,
                     required: #required,
                     options: #children,
-                    func: #func
+                    func: #func,
+                    aliases: #aliases,
+                    cooldown: #cooldown,
                 }
             },
-            ArgType::String(_) | ArgType::Integer(_) => quote_spanned! {span=>
+            ArgType::String(_) => quote_spanned! {span=>
                 #ty {
                     name: #name,
                     description: #description,
@@ -180,6 +518,16 @@ impl ToTokens for Argument {
                     choices: #options,
                 }
             },
+            ArgType::Integer(_) => quote_spanned! {span=>
+                #ty {
+                    name: #name,
+                    description: #description,
+                    required: #required,
+                    choices: #options,
+                    min_value: None,
+                    max_value: None,
+                }
+            },
             _ => quote_spanned! {span=>
                 #ty {
                     name: #name,
@@ -193,46 +541,95 @@ impl ToTokens for Argument {
 
 impl Parse for Argument {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
         let required = input.parse::<Required>()?;
         let ty = input.parse::<ArgType>()?;
         let name = input.call(Ident::parse_any)?;
+        check_name(&name)?;
 
+        let is_subcommand = ty.is_subcommand();
         let mut func = None;
         if input.peek(Token![=]) {
-            input.parse::<Token![=]>()?;
+            let eq = input.parse::<Token![=]>()?;
+            if !is_subcommand {
+                return Err(Error::new(
+                    eq.span(),
+                    "Only SubCommand and SubCommandGroup arguments can have a function",
+                ));
+            }
             func = Some(input.parse()?);
         }
 
-        input.parse::<Token![|]>()?;
-        let description = input.parse()?;
+        let description = if input.peek(Token![|]) {
+            input.parse::<Token![|]>()?;
+            input.parse()?
+        } else {
+            doc_comment(&attrs, name.span()).ok_or_else(|| {
+                Error::new(
+                    name.span(),
+                    "Arguments need a description, add a doc comment or `| \"description\"`",
+                )
+            })?
+        };
+        check_description(&description)?;
+
+        let mut aliases = None;
+        let mut cooldown = None;
+        loop {
+            if input.peek(kw::aliases) {
+                let keyword = input.parse::<kw::aliases>()?;
+                if !is_subcommand {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "Only SubCommand and SubCommandGroup arguments can have aliases",
+                    ));
+                }
+
+                let content;
+                parenthesized!(content in input);
+                aliases = Some(content.parse_terminated(<LitStr as Parse>::parse)?);
+            } else if input.peek(kw::cooldown) {
+                let keyword = input.parse::<kw::cooldown>()?;
+                if !is_subcommand {
+                    return Err(Error::new(
+                        keyword.span(),
+                        "Only SubCommand and SubCommandGroup arguments can have a cooldown",
+                    ));
+                }
+
+                let content;
+                parenthesized!(content in input);
+                cooldown = Some(content.parse()?);
+            } else {
+                break;
+            }
+        }
 
         let mut options = None;
         if input.peek(Brace) {
-            options = Some(input.parse()?);
+            let choices = input.parse::<ArgumentOption>()?;
+            if !matches!(ty, ArgType::String(_) | ArgType::Integer(_)) {
+                return Err(Error::new(
+                    choices.brace.span,
+                    "Only String and Integer arguments can have choices",
+                ));
+            }
+            choices.check()?;
+            options = Some(choices);
         }
 
         let mut children = None;
         if input.peek(Bracket) {
             let content;
-            bracketed!(content in input);
-            let raw_children = content.parse_terminated(Argument::parse)?;
-
-            let mut optional = false;
-
-            for child in &raw_children {
-                match child.required {
-                    Required::Required(r) =>
-                        if optional {
-                            return Err(Error::new(
-                                r.span(),
-                                "Cannot have required argument after optional argument",
-                            ));
-                        },
-                    Required::Optional(_) => {
-                        optional = true;
-                    }
-                }
+            let bracket = bracketed!(content in input);
+            if !is_subcommand {
+                return Err(Error::new(
+                    bracket.span,
+                    "Only SubCommand and SubCommandGroup arguments can have children",
+                ));
             }
+            let raw_children = content.parse_terminated(Argument::parse)?;
+            check_children(&raw_children, Some(&ty))?;
 
             children = Some(raw_children);
         }
@@ -243,21 +640,190 @@ impl Parse for Argument {
             name,
             options,
             description,
+            aliases,
+            cooldown,
             children,
             func,
         })
     }
 }
 
-struct ArgumentOption {
-    options: Punctuated<ChoiceMap, Token![,]>,
+impl Argument {
+    /// The combined length of the argument's name, description, choices and children
+    fn length(&self) -> usize {
+        let choices = self
+            .options
+            .iter()
+            .flat_map(|o| &o.options)
+            .map(|choice| {
+                let value = match &choice.value {
+                    Lit::Str(s) => s.value().chars().count(),
+                    value => value.to_token_stream().to_string().len(),
+                };
+                choice.key.value().chars().count() + value
+            })
+            .sum::<usize>();
+        let children = self
+            .children
+            .iter()
+            .flatten()
+            .map(Argument::length)
+            .sum::<usize>();
+
+        self.name.unraw().to_string().chars().count()
+            + self.description.value().chars().count()
+            + choices
+            + children
+    }
+}
+
+/// Checks the rules discord has for the arguments of a command or SubCommand
+///
+/// `parent` is the type of the argument they belong to, `None` for the command itself
+fn check_children(
+    children: &Punctuated<Argument, Comma>,
+    parent: Option<&ArgType>,
+) -> syn::Result<()> {
+    if let Some(extra) = children.iter().nth(MAX_OPTIONS) {
+        return Err(Error::new(
+            extra.name.span(),
+            format!("Cannot have more than {MAX_OPTIONS} arguments"),
+        ));
+    }
+
+    let has_subcommands = children.iter().any(|c| c.ty.is_subcommand());
+    let mut optional = false;
+    for (i, child) in children.iter().enumerate() {
+        match child.required {
+            Required::Required(r) =>
+                if optional {
+                    return Err(Error::new(
+                        r.span(),
+                        "Cannot have required argument after optional argument",
+                    ));
+                },
+            Required::Optional(_) => {
+                optional = true;
+            }
+        }
+
+        let error = match (parent, &child.ty) {
+            (Some(ArgType::SubCommandGroup(_)), ArgType::SubCommand(_)) => None,
+            (Some(ArgType::SubCommandGroup(_)), _) =>
+                Some("SubCommandGroups can only contain SubCommands"),
+            (Some(ArgType::SubCommand(_)), ty) if ty.is_subcommand() =>
+                Some("SubCommands can't contain SubCommands or SubCommandGroups"),
+            (_, ty) if has_subcommands && !ty.is_subcommand() =>
+                Some("Cannot mix SubCommands or SubCommandGroups with other arguments"),
+            _ => None,
+        };
+        if let Some(error) = error {
+            return Err(Error::new(child.name.span(), error));
+        }
+
+        if children
+            .iter()
+            .take(i)
+            .any(|c| c.name.unraw() == child.name.unraw())
+        {
+            return Err(Error::new(
+                child.name.span(),
+                format!("Duplicate argument name `{}`", child.name.unraw()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks a command or argument name follows discord's rules
+pub(crate) fn check_name(name: &Ident) -> syn::Result<()> {
+    let text = name.unraw().to_string();
+
+    if text.chars().count() > MAX_NAME_LENGTH {
+        Err(Error::new(
+            name.span(),
+            format!("Names can't be more than {MAX_NAME_LENGTH} characters long"),
+        ))
+    } else if text.chars().any(char::is_uppercase) {
+        Err(Error::new(name.span(), "Names must be lowercase"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Joins the lines of a doc comment into one, `None` if there isn't one
+pub(crate) fn doc_comment(attrs: &[Attribute], span: proc_macro2::Span) -> Option<LitStr> {
+    let lines = attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("doc"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::NameValue(MetaNameValue {
+                lit: Lit::Str(line),
+                ..
+            })) => Some(line.value().trim().to_owned()),
+            _ => None,
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(LitStr::new(&lines.join(" "), span))
+    }
+}
+
+/// Checks a description or choice is 1 to 100 characters long
+pub(crate) fn check_description(description: &LitStr) -> syn::Result<()> {
+    check_length(description, "Descriptions")
+}
+
+pub(crate) fn check_length(text: &LitStr, kind: &str) -> syn::Result<()> {
+    let length = text.value().chars().count();
+
+    if length == 0 || length > MAX_DESCRIPTION_LENGTH {
+        Err(Error::new(
+            text.span(),
+            format!("{kind} must be 1 to {MAX_DESCRIPTION_LENGTH} characters long, found {length}"),
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+pub(crate) struct ArgumentOption {
+    brace: Brace,
+    pub(crate) options: Punctuated<ChoiceMap, Token![,]>,
+}
+
+impl ArgumentOption {
+    /// Checks the choices follow discord's rules
+    pub(crate) fn check(&self) -> syn::Result<()> {
+        if let Some(extra) = self.options.iter().nth(MAX_CHOICES) {
+            return Err(Error::new(
+                extra.key.span(),
+                format!("Cannot have more than {MAX_CHOICES} choices"),
+            ));
+        }
+
+        for choice in &self.options {
+            check_length(&choice.key, "Choice names")?;
+            if let Lit::Str(value) = &choice.value {
+                check_length(value, "Choice values")?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Parse for ArgumentOption {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let content;
-        braced!(content in input);
+        let brace = braced!(content in input);
         Ok(ArgumentOption {
+            brace,
             options: content.parse_terminated(ChoiceMap::parse)?,
         })
     }
@@ -270,16 +836,18 @@ impl ToTokens for ArgumentOption {
     }
 }
 
-struct ChoiceMap {
-    key: Literal,
-    value: Literal,
+pub(crate) struct ChoiceMap {
+    key: LitStr,
+    pub(crate) value: Lit,
 }
 
 impl ToTokens for ChoiceMap {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         let key = &self.key;
         let value = &self.value;
-        tokens.append_all(quote! {ArgumentChoice{ name: #key, value: #value.to_owned()}})
+        tokens.append_all(
+            quote! {::slashy::commands::ArgumentChoice{ name: #key, value: #value.to_owned()}},
+        )
     }
 }
 
@@ -332,17 +900,24 @@ enum ArgType {
     Role(kw::Role),
 }
 
+impl ArgType {
+    fn is_subcommand(&self) -> bool {
+        matches!(self, ArgType::SubCommand(_) | ArgType::SubCommandGroup(_))
+    }
+}
+
 impl ToTokens for ArgType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
         tokens.append_all(match self {
-            ArgType::SubCommand(_) => quote! {CommandArguments::SubCommand},
-            ArgType::SubCommandGroup(_) => quote! {CommandArguments::SubCommandGroup},
-            ArgType::String(_) => quote! {CommandArguments::String},
-            ArgType::Integer(_) => quote! {CommandArguments::Integer},
-            ArgType::Boolean(_) => quote! {CommandArguments::Boolean},
-            ArgType::User(_) => quote! {CommandArguments::User},
-            ArgType::Channel(_) => quote! {CommandArguments::Channel},
-            ArgType::Role(_) => quote! {CommandArguments::Role},
+            ArgType::SubCommand(_) => quote! {::slashy::commands::CommandArguments::SubCommand},
+            ArgType::SubCommandGroup(_) =>
+                quote! {::slashy::commands::CommandArguments::SubCommandGroup},
+            ArgType::String(_) => quote! {::slashy::commands::CommandArguments::String},
+            ArgType::Integer(_) => quote! {::slashy::commands::CommandArguments::Integer},
+            ArgType::Boolean(_) => quote! {::slashy::commands::CommandArguments::Boolean},
+            ArgType::User(_) => quote! {::slashy::commands::CommandArguments::User},
+            ArgType::Channel(_) => quote! {::slashy::commands::CommandArguments::Channel},
+            ArgType::Role(_) => quote! {::slashy::commands::CommandArguments::Role},
         })
     }
 }
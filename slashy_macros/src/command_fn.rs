@@ -0,0 +1,102 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, spanned::Spanned, Error, FnArg, ItemFn, Pat, Token};
+
+use crate::{
+    args::TypedArgument,
+    command::{check_description, check_name, command_init, doc_comment, CommandModifier},
+};
+
+/// Generates a command from a function with the `#[command]` attribute
+///
+/// The function's parameters after the CommandContext become the command's arguments
+/// and are read from the context before the function's body runs.
+pub fn format_command_fn(
+    modifiers: Punctuated<CommandModifier, Token![,]>,
+    func: ItemFn,
+) -> syn::Result<TokenStream> {
+    for modifier in &modifiers {
+        if let CommandModifier::Args(ty) = modifier {
+            return Err(Error::new(
+                ty.span(),
+                "The arguments of a command function are its parameters",
+            ));
+        }
+    }
+
+    let name = &func.sig.ident;
+    check_name(name)?;
+    if func.sig.asyncness.is_none() {
+        return Err(Error::new(
+            func.sig.fn_token.span(),
+            "Command functions must be async",
+        ));
+    }
+    let description = doc_comment(&func.attrs, name.span()).ok_or_else(|| {
+        Error::new(
+            name.span(),
+            "Commands need a description, add a doc comment to the function",
+        )
+    })?;
+    check_description(&description)?;
+
+    let mut inputs = func.sig.inputs.iter();
+    let ctx = match inputs.next() {
+        Some(FnArg::Typed(ctx)) => ctx,
+        _ =>
+            return Err(Error::new(
+                func.sig.inputs.span(),
+                "Expected a CommandContext as the first parameter",
+            )),
+    };
+    let ctx_pat = &ctx.pat;
+
+    let mut arguments = Vec::new();
+    let mut readers = Vec::new();
+    let mut optional = false;
+    for input in inputs {
+        let param = match input {
+            FnArg::Typed(param) => param,
+            FnArg::Receiver(r) => return Err(Error::new(r.span(), "Commands can't take self")),
+        };
+        let ident = match &*param.pat {
+            Pat::Ident(pat) => &pat.ident,
+            pat => return Err(Error::new(pat.span(), "Expected an argument name")),
+        };
+        let argument = TypedArgument::new(ident, &param.ty, &param.attrs, &mut optional)?;
+        arguments.push(argument.command_argument());
+
+        let arg_name = &argument.name;
+        let read = argument.read(quote! {#ctx_pat.get_arg(#arg_name)});
+        let pat = &param.pat;
+        let ty = &param.ty;
+        readers.push(quote! {let #pat: #ty = #read;});
+    }
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = &func;
+    let output = &sig.output;
+    let modifiers = modifiers.into_iter().collect::<Vec<_>>();
+    let init = command_init(
+        name,
+        &description,
+        quote! {Some(vec![#(#arguments),*])},
+        quote! {Some(#name)},
+        &modifiers,
+    );
+
+    Ok(quote! {
+        #(#attrs)*
+        #[::slashy::subcommand]
+        #vis async fn #name(#ctx) #output {
+            #(#readers)*
+            #block
+        }
+
+        #init
+    })
+}
@@ -3,12 +3,28 @@
 use proc_macro2::Span;
 use quote::{quote, ToTokens};
 use subcommand::format_subcommand;
-use syn::{parse_macro_input, FnArg, ItemFn, Lifetime, ReturnType, Type};
+use syn::{
+    parse_macro_input,
+    punctuated::Punctuated,
+    DeriveInput,
+    FnArg,
+    ItemFn,
+    Lifetime,
+    ReturnType,
+    Token,
+    Type,
+};
 
 extern crate proc_macro;
 
+mod args;
+use args::derive_slashy_args;
+mod choice;
+use choice::derive_slashy_choice;
 mod command;
-use command::CommandInput;
+use command::{CommandInput, CommandModifier};
+mod command_fn;
+use command_fn::format_command_fn;
 mod subcommand;
 use subcommand::*;
 
@@ -20,6 +36,20 @@ pub fn command(input_stream: proc_macro::TokenStream) -> proc_macro::TokenStream
     output.to_token_stream().into()
 }
 
+#[proc_macro_attribute]
+pub fn command_attribute(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let modifiers =
+        parse_macro_input!(attr with Punctuated::<CommandModifier, Token![,]>::parse_terminated);
+    let func = parse_macro_input!(item as ItemFn);
+    match format_command_fn(modifiers, func) {
+        Ok(t) => t.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
 #[proc_macro_attribute]
 pub fn permissions_check(
     _attr: proc_macro::TokenStream,
@@ -78,3 +108,21 @@ pub fn subcommand(
         Err(e) => e.to_compile_error().into(),
     }
 }
+
+#[proc_macro_derive(SlashyArgs, attributes(arg))]
+pub fn slashy_args(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_slashy_args(input) {
+        Ok(t) => t.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[proc_macro_derive(SlashyChoice, attributes(choice))]
+pub fn slashy_choice(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_slashy_choice(input) {
+        Ok(t) => t.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -1,5 +1,5 @@
 use proc_macro2::{Ident, Span, TokenStream};
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{
     ext::IdentExt,
     parse::Parse,
@@ -9,11 +9,14 @@ use syn::{
     FnArg,
     ItemFn,
     Lifetime,
+    LitStr,
     ReturnType,
     Token,
     Type,
 };
 
+use crate::command::doc_comment;
+
 pub fn format_subcommand(func: SubCommandFunc, args: SubCommandArgs) -> Result<TokenStream, Error> {
     let perms = match args.perms_checks {
         Some(p) => p,
@@ -93,7 +96,17 @@ pub fn format_subcommand(func: SubCommandFunc, args: SubCommandArgs) -> Result<T
     };
 
 
+    // Lets command! use the doc comment as the command's description,
+    // the constant is named after the function so it can be imported along with it
+    let description =
+        doc_comment(&attrs, name.span()).unwrap_or_else(|| LitStr::new("", name.span()));
+    let description_name = description_const(&name);
+
     Ok(quote! {
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        #vis const #description_name: &str = #description;
+
         #(#attrs)*
         #vis fn #name<'fut>(#(#input),*) -> ::slashy::serenity::futures::future::BoxFuture<'fut, #return_ty> {
             use ::slashy::serenity::futures::future::FutureExt;
@@ -107,6 +120,15 @@ pub fn format_subcommand(func: SubCommandFunc, args: SubCommandArgs) -> Result<T
 }
 
 
+/// The name of the constant #[subcommand] stores a function's doc comment in
+pub(crate) fn description_const(name: &Ident) -> Ident {
+    format_ident!(
+        "__SLASHY_DESC_{}",
+        name.unraw().to_string().to_uppercase(),
+        span = name.span()
+    )
+}
+
 pub struct SubCommandFunc {
     block: ItemFn,
 }